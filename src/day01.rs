@@ -1,10 +1,214 @@
-use std::num::ParseIntError;
+use std::{collections::BTreeMap, fmt::Write, num::ParseIntError};
 
 advent_of_code::day!(01);
 
 type ParseError = ParseIntError;
 type Depth = i32;
 
+#[derive(Debug, PartialEq)]
+pub struct WindowAnalysis {
+    pub increases: Vec<usize>,
+    pub longest_run: usize,
+}
+
+impl WindowAnalysis {
+    pub fn count(&self) -> usize {
+        self.increases.len()
+    }
+}
+
+/// Compares the sums of consecutive `size`-wide windows, recording the index of every window
+/// whose sum is larger than the one before it. Two such windows share all but one reading, so
+/// only the readings entering and leaving need comparing.
+pub fn analyse_windows(depths: &[Depth], size: usize) -> WindowAnalysis {
+    let increases = match size {
+        0 => Vec::new(),
+        _ => depths
+            .windows(size + 1)
+            .enumerate()
+            .filter(|(_, w)| w[size] > w[0])
+            .map(|(idx, _)| idx + 1)
+            .collect::<Vec<_>>(),
+    };
+
+    let (longest_run, _) = increases
+        .iter()
+        .fold((0, None), |(longest, run), &idx| match run {
+            Some((start, end)) if end + 1 == idx => {
+                (usize::max(longest, idx - start + 1), Some((start, idx)))
+            }
+            _ => (usize::max(longest, 1), Some((idx, idx))),
+        });
+
+    WindowAnalysis {
+        increases,
+        longest_run,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DepthStats {
+    pub min: Depth,
+    pub max: Depth,
+    pub biggest_rise: Option<(usize, i64)>,
+    pub biggest_drop: Option<(usize, i64)>,
+    pub bucket: i64,
+    pub histogram: Vec<(i64, usize)>,
+    pub window: usize,
+    pub smoothed: Vec<i64>,
+}
+
+/// Buckets the step-to-step changes into `bucket`-wide ranges and smooths the series with the
+/// sums of `window`-wide sliding windows, the same sums that part 2 compares. Changes and sums
+/// are widened to `i64`, since extreme depths overflow a `Depth`.
+pub fn stats(depths: &[Depth], window: usize, bucket: Depth) -> Option<DepthStats> {
+    let min = *depths.iter().min()?;
+    let max = *depths.iter().max()?;
+
+    let changes = depths
+        .windows(2)
+        .enumerate()
+        .map(|(idx, w)| (idx + 1, w[1] as i64 - w[0] as i64))
+        .collect::<Vec<_>>();
+    let biggest_rise = changes
+        .iter()
+        .copied()
+        .filter(|&(_, c)| c > 0)
+        .max_by_key(|&(idx, c)| (c, usize::MAX - idx));
+    let biggest_drop = changes
+        .iter()
+        .copied()
+        .filter(|&(_, c)| c < 0)
+        .min_by_key(|&(idx, c)| (c, idx));
+
+    let bucket = bucket.max(1) as i64;
+    let histogram = changes
+        .iter()
+        .fold(BTreeMap::new(), |mut acc, (_, c)| {
+            *acc.entry(c.div_euclid(bucket) * bucket).or_insert(0) += 1;
+            acc
+        })
+        .into_iter()
+        .collect();
+
+    let smoothed = match window {
+        0 => Vec::new(),
+        _ => depths
+            .windows(window)
+            .map(|w| w.iter().map(|&d| d as i64).sum())
+            .collect(),
+    };
+
+    Some(DepthStats {
+        min,
+        max,
+        biggest_rise,
+        biggest_drop,
+        bucket,
+        histogram,
+        window,
+        smoothed,
+    })
+}
+
+impl DepthStats {
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+
+        writeln!(out, "min depth: {}", self.min).unwrap();
+        writeln!(out, "max depth: {}", self.max).unwrap();
+        match self.biggest_rise {
+            Some((idx, c)) => writeln!(out, "biggest rise: {:+} at reading {}", c, idx),
+            None => writeln!(out, "biggest rise: none"),
+        }
+        .unwrap();
+        match self.biggest_drop {
+            Some((idx, c)) => writeln!(out, "biggest drop: {:+} at reading {}", c, idx),
+            None => writeln!(out, "biggest drop: none"),
+        }
+        .unwrap();
+
+        let most = self.histogram.iter().map(|&(_, n)| n).max().unwrap_or(1);
+        writeln!(out, "changes:").unwrap();
+        for &(start, n) in &self.histogram {
+            let label = match self.bucket {
+                1 => start.to_string(),
+                bucket => format!("{}..={}", start, start + bucket - 1),
+            };
+            let bar = "#".repeat((n * 50).div_ceil(most));
+            writeln!(out, "{:>12} | {} {}", label, bar, n).unwrap();
+        }
+
+        writeln!(out, "smoothed over {}:", self.window).unwrap();
+        let smoothed = self.smoothed.iter().map(i64::to_string).collect::<Vec<_>>();
+        writeln!(out, "{}", smoothed.join(" ")).unwrap();
+
+        out
+    }
+
+    pub fn to_csv(&self, depths: &[Depth]) -> String {
+        let mut out = String::from("index,depth,change,window_sum\n");
+
+        for (idx, depth) in depths.iter().enumerate() {
+            let change = match idx {
+                0 => String::new(),
+                _ => (*depth as i64 - depths[idx - 1] as i64).to_string(),
+            };
+            let sum = self
+                .smoothed
+                .get(idx)
+                .map(i64::to_string)
+                .unwrap_or_default();
+
+            writeln!(out, "{},{},{},{}", idx, depth, change, sum).unwrap();
+        }
+
+        out
+    }
+}
+
+/// Tracks the part 1 and part 2 counts one reading at a time, remembering only the last three
+/// readings since that's as far back as a three-wide window comparison reaches.
+#[derive(Debug, Default)]
+pub struct DepthMonitor {
+    recent: [Depth; 3],
+    seen: usize,
+    increases: usize,
+    window_increases: usize,
+}
+
+impl DepthMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, depth: Depth) {
+        let slot = self.seen % 3;
+
+        if self.seen >= 1 && depth > self.recent[(self.seen - 1) % 3] {
+            self.increases += 1;
+        }
+        if self.seen >= 3 && depth > self.recent[slot] {
+            self.window_increases += 1;
+        }
+
+        self.recent[slot] = depth;
+        self.seen += 1;
+    }
+
+    pub fn seen(&self) -> usize {
+        self.seen
+    }
+
+    pub fn increases(&self) -> usize {
+        self.increases
+    }
+
+    pub fn window_increases(&self) -> usize {
+        self.window_increases
+    }
+}
+
 impl advent_of_code::Solution<'_> for Day01 {
     type Input = Vec<Depth>;
     type ParseError = ParseError;
@@ -17,17 +221,11 @@ impl advent_of_code::Solution<'_> for Day01 {
     }
 
     fn part1(input: &[Depth]) -> Self::P1 {
-        input
-            .array_windows::<2>()
-            .filter(|[x1, x2]| x2 > x1)
-            .count()
+        analyse_windows(input, 1).count()
     }
 
     fn part2(input: &[Depth]) -> Self::P2 {
-        input
-            .array_windows::<4>()
-            .filter(|[a, _, _, d]| d > a)
-            .count()
+        analyse_windows(input, 3).count()
     }
 }
 
@@ -50,4 +248,69 @@ mod tests {
     fn test() {
         assert_eq!(super::Day01::solve(INPUT), Ok((7, 5)));
     }
+
+    #[test]
+    fn test_windows() {
+        let depths = super::Day01::parse(INPUT).unwrap();
+
+        assert_eq!(
+            super::analyse_windows(&depths, 1),
+            super::WindowAnalysis {
+                increases: vec![1, 2, 3, 5, 6, 7, 9],
+                longest_run: 3,
+            }
+        );
+        assert_eq!(
+            super::analyse_windows(&depths, 3).increases,
+            vec![1, 4, 5, 6, 7]
+        );
+        assert_eq!(super::analyse_windows(&depths, 10).count(), 0);
+        assert_eq!(super::analyse_windows(&depths, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_monitor() {
+        let mut monitor = super::DepthMonitor::new();
+        super::Day01::parse(INPUT)
+            .unwrap()
+            .into_iter()
+            .for_each(|d| monitor.push(d));
+
+        assert_eq!(
+            (
+                monitor.seen(),
+                monitor.increases(),
+                monitor.window_increases()
+            ),
+            (10, 7, 5)
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let depths = super::Day01::parse(INPUT).unwrap();
+        let stats = super::stats(&depths, 3, 5).unwrap();
+
+        assert_eq!((stats.min, stats.max), (199, 269));
+        assert_eq!(stats.biggest_rise, Some((6, 33)));
+        assert_eq!(stats.biggest_drop, Some((4, -10)));
+        assert_eq!(
+            stats.histogram,
+            vec![(-10, 2), (0, 3), (5, 2), (25, 1), (30, 1)]
+        );
+        assert_eq!(stats.smoothed, vec![607, 618, 618, 617, 647, 716, 769, 792]);
+        assert!(stats
+            .to_csv(&depths)
+            .starts_with("index,depth,change,window_sum\n0,199,,607\n1,200,1,618\n"));
+
+        let extreme = super::Day01::parse("2147483647\n-2147483648\n5").unwrap();
+        let stats = super::stats(&extreme, 2, 1).unwrap();
+
+        assert_eq!(stats.biggest_rise, Some((2, 2147483653)));
+        assert_eq!(stats.biggest_drop, Some((1, -4294967295)));
+        assert_eq!(stats.smoothed, vec![-1, -2147483643]);
+        assert!(stats
+            .to_csv(&extreme)
+            .ends_with("1,-2147483648,-4294967295,-2147483643\n2,5,2147483653,\n"));
+    }
 }
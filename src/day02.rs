@@ -1,70 +1,549 @@
-use std::{num::ParseIntError, str};
+use std::{fmt::Write, iter, num::ParseIntError, slice, str, vec};
 
 advent_of_code::day!(02);
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Command {
     Forward(u32),
+    Back(u32),
     Down(u32),
     Up(u32),
+    Left(u32),
+    Right(u32),
 }
 
-impl str::FromStr for Command {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (command, count) = s.split_once(' ').ok_or(ParseError::ExpectedWhitespace)?;
-
+impl Command {
+    fn new(command: &str, count: &str) -> Result<Self, ParseError> {
         let count = count.parse()?;
 
         match command {
             "forward" => Ok(Self::Forward(count)),
+            "back" => Ok(Self::Back(count)),
             "down" => Ok(Self::Down(count)),
             "up" => Ok(Self::Up(count)),
+            "left" => Ok(Self::Left(count)),
+            "right" => Ok(Self::Right(count)),
             _ => Err(ParseError::UnknownCommand(String::from(command))),
         }
     }
 }
 
+impl str::FromStr for Command {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (command, count) = s.split_once(' ').ok_or(ParseError::ExpectedWhitespace)?;
+
+        Self::new(command, count)
+    }
+}
+
+/// A navigation script: the AoC commands plus `back`, `left`, `right`, `#` comments, `repeat N { ... }` blocks
+/// and an optional `steering <model>` line picking the model the script is meant for.
+#[derive(Debug, PartialEq)]
+pub struct Script {
+    pub steering: Option<Steering>,
+    pub commands: Vec<Command>,
+}
+
+fn tokens(s: &str) -> Vec<(usize, &str)> {
+    s.lines()
+        .enumerate()
+        .flat_map(|(idx, l)| {
+            let code = l.split('#').next().unwrap_or_default();
+
+            code.split_ascii_whitespace()
+                .flat_map(|word| {
+                    let mut parts = Vec::new();
+                    let mut start = 0;
+                    for (i, c) in word.char_indices().filter(|&(_, c)| c == '{' || c == '}') {
+                        if start < i {
+                            parts.push(&word[start..i]);
+                        }
+                        parts.push(&word[i..i + c.len_utf8()]);
+                        start = i + 1;
+                    }
+                    if start < word.len() {
+                        parts.push(&word[start..]);
+                    }
+
+                    parts
+                })
+                .map(move |t| (idx + 1, t))
+        })
+        .collect()
+}
+
+/// Repeats are expanded while parsing, so the expanded script is capped to keep a large repeat
+/// count from exhausting memory.
+const MAX_COMMANDS: usize = 1 << 20;
+
+struct ScriptParser<'a> {
+    tokens: iter::Peekable<vec::IntoIter<(usize, &'a str)>>,
+    steering: Option<Steering>,
+}
+
+impl<'a> ScriptParser<'a> {
+    fn argument(&mut self, line: usize) -> Result<&'a str, ScriptError> {
+        match self
+            .tokens
+            .next_if(|&(l, t)| l == line && t != "{" && t != "}")
+        {
+            Some((_, arg)) => Ok(arg),
+            None => Err(ScriptError::new(line, ParseError::ExpectedArgument)),
+        }
+    }
+
+    fn block(&mut self, opened: Option<usize>) -> Result<Vec<Command>, ScriptError> {
+        let mut commands = Vec::new();
+
+        loop {
+            match (self.tokens.next(), opened) {
+                (None, None) | (Some((_, "}")), Some(_)) => return Ok(commands),
+                (None, Some(line)) => {
+                    return Err(ScriptError::new(line, ParseError::UnclosedBlock))
+                }
+                (Some((line, "}")), None) => {
+                    return Err(ScriptError::new(line, ParseError::UnexpectedBrace))
+                }
+                (Some((line, "steering")), _) => {
+                    let name = self.argument(line)?;
+                    if opened.is_some() || self.steering.is_some() {
+                        return Err(ScriptError::new(line, ParseError::MisplacedSteering));
+                    }
+
+                    self.steering = Some(name.parse().map_err(|err| ScriptError::new(line, err))?);
+                }
+                (Some((line, "repeat")), _) => {
+                    let times = self
+                        .argument(line)?
+                        .parse::<usize>()
+                        .map_err(|err| ScriptError::new(line, err.into()))?;
+                    if self.tokens.next_if(|&(_, t)| t == "{").is_none() {
+                        return Err(ScriptError::new(line, ParseError::ExpectedBrace));
+                    }
+
+                    let body = self.block(Some(line))?;
+                    let expanded = times
+                        .checked_mul(body.len())
+                        .and_then(|len| len.checked_add(commands.len()));
+                    if !matches!(expanded, Some(len) if len <= MAX_COMMANDS) {
+                        return Err(ScriptError::new(line, ParseError::TooManyCommands));
+                    }
+
+                    for _ in 0..times {
+                        commands.extend_from_slice(&body);
+                    }
+                }
+                (Some((line, command)), _) => {
+                    let count = self.argument(line)?;
+
+                    commands.push(
+                        Command::new(command, count).map_err(|err| ScriptError::new(line, err))?,
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl str::FromStr for Script {
+    type Err = ScriptError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = ScriptParser {
+            tokens: tokens(s).into_iter().peekable(),
+            steering: None,
+        };
+        let commands = parser.block(None)?;
+
+        Ok(Self {
+            steering: parser.steering,
+            commands,
+        })
+    }
+}
+
 impl advent_of_code::Solution<'_> for Day02 {
     type Input = Vec<Command>;
     type ParseError = ParseError;
 
-    type P1 = u32;
-    type P2 = u32;
+    type P1 = Result<i64, CourseError>;
+    type P2 = Result<i64, CourseError>;
 
     fn parse(input: &str) -> Result<Self::Input, Self::ParseError> {
         input.lines().map(str::parse).collect()
     }
 
     fn part1(input: &[Command]) -> Self::P1 {
-        let (f, d) = input.iter().fold((0, 0), |(x, y), comm| match comm {
-            Command::Down(cy) => (x, y + cy),
-            Command::Up(cy) => (x, y - cy),
-            Command::Forward(cx) => (x + cx, y),
-        });
-
-        f * d
+        product(input, Direct)
     }
 
     fn part2(input: &[Command]) -> Self::P2 {
-        let (f, d, _) = input.iter().fold((0, 0, 0), |(x, y, a), comm| match comm {
-            Command::Down(ca) => (x, y, a + ca),
-            Command::Up(ca) => (x, y, a - ca),
-            Command::Forward(cx) => (x + cx, y + a * cx, a),
-        });
+        product(input, Aim)
+    }
+}
+
+/// The values a steering model tracks, exposed generically so traces can be exported.
+pub trait Telemetry: Copy + Default + PartialEq + fmt::Debug {
+    const COLUMNS: &'static [&'static str];
+
+    fn position(&self) -> i64;
+
+    fn depth(&self) -> i64;
+
+    fn values(&self) -> Vec<i64>;
+}
+
+pub trait SteeringModel {
+    type State: Telemetry;
+
+    fn apply(&self, state: &mut Self::State, command: &Command) -> Result<(), StepError>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepError {
+    Overflow,
+    Unsupported,
+}
+
+fn checked(value: Option<i64>) -> Result<i64, StepError> {
+    value.ok_or(StepError::Overflow)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Position {
+    pub position: i64,
+    pub depth: i64,
+}
+
+impl Telemetry for Position {
+    const COLUMNS: &'static [&'static str] = &["position", "depth"];
+
+    fn position(&self) -> i64 {
+        self.position
+    }
+
+    fn depth(&self) -> i64 {
+        self.depth
+    }
+
+    fn values(&self) -> Vec<i64> {
+        vec![self.position, self.depth]
+    }
+}
+
+/// Part 1: `down` and `up` change the depth directly.
+#[derive(Debug, Clone, Copy)]
+pub struct Direct;
+
+impl SteeringModel for Direct {
+    type State = Position;
+
+    fn apply(&self, state: &mut Self::State, command: &Command) -> Result<(), StepError> {
+        match *command {
+            Command::Forward(n) => state.position = checked(state.position.checked_add(n as i64))?,
+            Command::Back(n) => state.position = checked(state.position.checked_sub(n as i64))?,
+            Command::Down(n) => state.depth = checked(state.depth.checked_add(n as i64))?,
+            Command::Up(n) => state.depth = checked(state.depth.checked_sub(n as i64))?,
+            Command::Left(_) | Command::Right(_) => return Err(StepError::Unsupported),
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Aimed {
+    pub position: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+impl Telemetry for Aimed {
+    const COLUMNS: &'static [&'static str] = &["position", "depth", "aim"];
+
+    fn position(&self) -> i64 {
+        self.position
+    }
+
+    fn depth(&self) -> i64 {
+        self.depth
+    }
+
+    fn values(&self) -> Vec<i64> {
+        vec![self.position, self.depth, self.aim]
+    }
+}
+
+/// Part 2: `down` and `up` change the aim, which `forward` and `back` then dive along.
+#[derive(Debug, Clone, Copy)]
+pub struct Aim;
+
+impl SteeringModel for Aim {
+    type State = Aimed;
+
+    fn apply(&self, state: &mut Self::State, command: &Command) -> Result<(), StepError> {
+        match *command {
+            Command::Forward(n) => {
+                state.position = checked(state.position.checked_add(n as i64))?;
+                state.depth = checked(
+                    state
+                        .aim
+                        .checked_mul(n as i64)
+                        .and_then(|dy| state.depth.checked_add(dy)),
+                )?;
+            }
+            Command::Back(n) => {
+                state.position = checked(state.position.checked_sub(n as i64))?;
+                state.depth = checked(
+                    state
+                        .aim
+                        .checked_mul(n as i64)
+                        .and_then(|dy| state.depth.checked_sub(dy)),
+                )?;
+            }
+            Command::Down(n) => state.aim = checked(state.aim.checked_add(n as i64))?,
+            Command::Up(n) => state.aim = checked(state.aim.checked_sub(n as i64))?,
+            Command::Left(_) | Command::Right(_) => return Err(StepError::Unsupported),
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Spatial {
+    pub position: i64,
+    pub depth: i64,
+    pub lateral: i64,
+    pub aim: i64,
+    pub yaw: i64,
+}
+
+impl Telemetry for Spatial {
+    const COLUMNS: &'static [&'static str] = &["position", "depth", "lateral", "aim", "yaw"];
+
+    fn position(&self) -> i64 {
+        self.position
+    }
+
+    fn depth(&self) -> i64 {
+        self.depth
+    }
 
-        f * d
+    fn values(&self) -> Vec<i64> {
+        vec![self.position, self.depth, self.lateral, self.aim, self.yaw]
     }
 }
 
+/// The aim model in three dimensions: `left` and `right` turn the yaw the same way `up` and
+/// `down` tilt the aim, and moving drifts sideways along it.
+#[derive(Debug, Clone, Copy)]
+pub struct Lateral;
+
+impl SteeringModel for Lateral {
+    type State = Spatial;
+
+    fn apply(&self, state: &mut Self::State, command: &Command) -> Result<(), StepError> {
+        let step = |n: u32, sign: i64| -> Result<Spatial, StepError> {
+            let n = sign * n as i64;
+            let drift =
+                |along: i64, by: i64| checked(by.checked_mul(n).and_then(|d| along.checked_add(d)));
+
+            Ok(Spatial {
+                position: checked(state.position.checked_add(n))?,
+                depth: drift(state.depth, state.aim)?,
+                lateral: drift(state.lateral, state.yaw)?,
+                ..*state
+            })
+        };
+
+        match *command {
+            Command::Forward(n) => *state = step(n, 1)?,
+            Command::Back(n) => *state = step(n, -1)?,
+            Command::Down(n) => state.aim = checked(state.aim.checked_add(n as i64))?,
+            Command::Up(n) => state.aim = checked(state.aim.checked_sub(n as i64))?,
+            Command::Right(n) => state.yaw = checked(state.yaw.checked_add(n as i64))?,
+            Command::Left(n) => state.yaw = checked(state.yaw.checked_sub(n as i64))?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Names the shipped models, so scripts and the command line can pick one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Steering {
+    Direct,
+    Aim,
+    Lateral,
+}
+
+impl str::FromStr for Steering {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "direct" => Ok(Self::Direct),
+            "aim" => Ok(Self::Aim),
+            "lateral" => Ok(Self::Lateral),
+            _ => Err(ParseError::UnknownSteering(String::from(s))),
+        }
+    }
+}
+
+/// Replays a course, yielding the state after every command. It stops after the first error.
+#[derive(Debug)]
+pub struct Trace<'a, M: SteeringModel> {
+    commands: iter::Enumerate<slice::Iter<'a, Command>>,
+    model: M,
+    state: Option<M::State>,
+}
+
+impl<M: SteeringModel> Iterator for Trace<'_, M> {
+    type Item = Result<M::State, CourseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut state = self.state.take()?;
+        let (idx, comm) = self.commands.next()?;
+
+        let res = match self.model.apply(&mut state, comm) {
+            Ok(()) if state.depth() < 0 => Err(CourseError::NegativeDepth(idx)),
+            Ok(()) => Ok(state),
+            Err(StepError::Overflow) => Err(CourseError::Overflow(idx)),
+            Err(StepError::Unsupported) => Err(CourseError::Unsupported(idx)),
+        };
+        self.state = res.as_ref().ok().copied();
+
+        Some(res)
+    }
+}
+
+pub fn trace<M: SteeringModel>(commands: &[Command], model: M) -> Trace<'_, M> {
+    Trace {
+        commands: commands.iter().enumerate(),
+        model,
+        state: Some(M::State::default()),
+    }
+}
+
+fn product<M: SteeringModel>(commands: &[Command], model: M) -> Result<i64, CourseError> {
+    let end = trace(commands, model).try_fold(M::State::default(), |_, state| state)?;
+
+    end.position()
+        .checked_mul(end.depth())
+        .ok_or(CourseError::ProductOverflow)
+}
+
+/// The exporters take the states as yielded by `trace` and prepend the starting state.
+pub fn path_csv<S: Telemetry>(states: &[S]) -> String {
+    let mut out = format!("step,{}\n", S::COLUMNS.join(","));
+
+    for (step, state) in iter::once(&S::default()).chain(states).enumerate() {
+        let values = state
+            .values()
+            .iter()
+            .map(i64::to_string)
+            .collect::<Vec<_>>();
+
+        writeln!(out, "{},{}", step, values.join(",")).unwrap();
+    }
+
+    out
+}
+
+fn bounds<S: Telemetry>(states: &[S]) -> (i64, i64, i64) {
+    states.iter().fold((0, 1, 1), |(min_x, max_x, max_y), s| {
+        (
+            i64::min(min_x, s.position()),
+            i64::max(max_x, s.position()),
+            i64::max(max_y, s.depth()),
+        )
+    })
+}
+
+pub fn depth_profile<S: Telemetry>(states: &[S], width: usize, height: usize) -> String {
+    let (min_x, max_x, max_y) = bounds(states);
+    let (width, height) = (width.max(1), height.max(1));
+    let mut grid = vec![vec![' '; width]; height];
+
+    for state in iter::once(&S::default()).chain(states) {
+        let col = ((state.position() - min_x) as i128 * (width - 1) as i128
+            / (max_x - min_x) as i128) as usize;
+        let row = (state.depth() as i128 * (height - 1) as i128 / max_y as i128) as usize;
+        grid[row][col] = '#';
+    }
+
+    let mut out = format!("depth 0..={}, position {}..={}\n", max_y, min_x, max_x);
+    for row in grid {
+        out.extend(row);
+        out.push('\n');
+    }
+
+    out
+}
+
+pub fn depth_profile_svg<S: Telemetry>(states: &[S], width: usize, height: usize) -> String {
+    let (min_x, max_x, max_y) = bounds(states);
+
+    let points = iter::once(&S::default())
+        .chain(states)
+        .map(|s| {
+            format!(
+                "{:.1},{:.1}",
+                (s.position() - min_x) as f64 / (max_x - min_x) as f64 * width as f64,
+                s.depth() as f64 / max_y as f64 * height as f64
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+            "<polyline fill=\"none\" stroke=\"#1f5fa0\" points=\"{2}\"/>\n",
+            "</svg>\n"
+        ),
+        width,
+        height,
+        points.join(" ")
+    )
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CourseError {
+    Overflow(usize),
+    NegativeDepth(usize),
+    Unsupported(usize),
+    ProductOverflow,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ParseError {
     UnknownCommand(String),
+    UnknownSteering(String),
     ExpectedWhitespace,
+    ExpectedArgument,
+    ExpectedBrace,
+    UnexpectedBrace,
+    UnclosedBlock,
+    MisplacedSteering,
+    TooManyCommands,
     NotAnInt(ParseIntError),
 }
 
+#[derive(Debug, PartialEq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub err: ParseError,
+}
+
+impl ScriptError {
+    fn new(line: usize, err: ParseError) -> Self {
+        Self { line, err }
+    }
+}
+
 impl From<ParseIntError> for ParseError {
     fn from(err: ParseIntError) -> Self {
         Self::NotAnInt(err)
@@ -77,12 +556,35 @@ impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::UnknownCommand(command) => write!(f, "unknown command \"{}\"", command),
+            Self::UnknownSteering(steering) => write!(f, "unknown steering \"{}\"", steering),
             Self::ExpectedWhitespace => write!(f, "expected whitespace"),
+            Self::ExpectedArgument => write!(f, "expected an argument on the same line"),
+            Self::ExpectedBrace => write!(f, "expected \"{{\" after repeat count"),
+            Self::UnexpectedBrace => write!(f, "unexpected \"}}\""),
+            Self::UnclosedBlock => write!(f, "repeat block is never closed"),
+            Self::TooManyCommands => {
+                write!(f, "repeat expands past {} commands", MAX_COMMANDS)
+            }
+            Self::MisplacedSteering => {
+                write!(f, "steering must be set once, outside of repeat blocks")
+            }
             Self::NotAnInt(parse_int_err) => write!(f, "{}", parse_int_err),
         }
     }
 }
 
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.err)
+    }
+}
+
+impl error::Error for ScriptError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.err)
+    }
+}
+
 impl error::Error for ParseError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
@@ -92,6 +594,27 @@ impl error::Error for ParseError {
     }
 }
 
+impl fmt::Display for CourseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow(idx) => write!(f, "command {} overflows the course", idx + 1),
+            Self::NegativeDepth(idx) => {
+                write!(f, "command {} takes the depth above the surface", idx + 1)
+            }
+            Self::Unsupported(idx) => {
+                write!(
+                    f,
+                    "command {} is not supported by this steering model",
+                    idx + 1
+                )
+            }
+            Self::ProductOverflow => write!(f, "position product overflows"),
+        }
+    }
+}
+
+impl error::Error for CourseError {}
+
 #[cfg(test)]
 mod tests {
     use advent_of_code::Solution;
@@ -105,6 +628,135 @@ forward 2";
 
     #[test]
     fn test() {
-        assert_eq!(super::Day02::solve(INPUT), Ok((150, 900)));
+        assert_eq!(super::Day02::solve(INPUT), Ok((Ok(150), Ok(900))));
+    }
+
+    #[test]
+    fn test_trace() {
+        use super::{Aim, Aimed};
+
+        let commands = super::Day02::parse(INPUT).unwrap();
+        let states = super::trace(&commands, Aim)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(states.len(), 6);
+        assert_eq!(
+            states[2],
+            Aimed {
+                position: 13,
+                depth: 40,
+                aim: 5
+            }
+        );
+        assert!(super::path_csv(&states).starts_with("step,position,depth,aim\n0,0,0,0\n1,5,0,0\n"));
+        assert_eq!(
+            super::depth_profile(&states, 4, 3),
+            "depth 0..=60, position 0..=15\n##  \n  # \n   #\n"
+        );
+    }
+
+    #[test]
+    fn test_script() {
+        use super::{Aim, Command, ParseError, Script, ScriptError, Steering};
+
+        const SCRIPT: &str = "# survey loop
+steering aim
+
+repeat 2 {
+    forward 5 # cruise
+    repeat 2 { down 1 }
+}
+back 2";
+
+        let script = SCRIPT.parse::<Script>().unwrap();
+        assert_eq!(script.steering, Some(Steering::Aim));
+        assert_eq!(script.commands.len(), 7);
+        assert_eq!(script.commands[6], Command::Back(2));
+
+        let end = super::trace(&script.commands, Aim).last().unwrap().unwrap();
+        assert_eq!((end.position, end.depth, end.aim), (8, 2, 4));
+
+        assert_eq!(
+            "forward 1\nrepeat 2 {\n  sideways 3\n}".parse::<Script>(),
+            Err(ScriptError {
+                line: 3,
+                err: ParseError::UnknownCommand(String::from("sideways"))
+            })
+        );
+        assert_eq!(
+            "repeat 2 {\nforward 1".parse::<Script>(),
+            Err(ScriptError {
+                line: 1,
+                err: ParseError::UnclosedBlock
+            })
+        );
+        assert_eq!(
+            "repeat 4000000000 {\n forward 1\n}".parse::<Script>(),
+            Err(ScriptError {
+                line: 1,
+                err: ParseError::TooManyCommands
+            })
+        );
+        assert_eq!(
+            "repeat 1024 {\n  repeat 1025 {\n    forward 1\n  }\n}".parse::<Script>(),
+            Err(ScriptError {
+                line: 1,
+                err: ParseError::TooManyCommands
+            })
+        );
+    }
+
+    #[test]
+    fn test_lateral() {
+        use super::{CourseError, Direct, Lateral, Spatial};
+
+        let commands = "forward 5\ndown 5\nright 2\nforward 8\nleft 3\nback 1"
+            .parse::<super::Script>()
+            .unwrap()
+            .commands;
+
+        assert_eq!(
+            super::trace(&commands, Lateral).last(),
+            Some(Ok(Spatial {
+                position: 12,
+                depth: 35,
+                lateral: 17,
+                aim: 5,
+                yaw: -1
+            }))
+        );
+        assert_eq!(
+            super::trace(&commands, Direct).last(),
+            Some(Err(CourseError::Unsupported(2)))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        use super::CourseError;
+
+        assert_eq!(
+            super::Day02::solve("down 1\nup 3\nforward 1"),
+            Ok((
+                Err(CourseError::NegativeDepth(1)),
+                Err(CourseError::NegativeDepth(2))
+            ))
+        );
+        assert_eq!(
+            super::Day02::solve("forward 4294967295\ndown 4294967295\nforward 4294967295"),
+            Ok((
+                Err(CourseError::ProductOverflow),
+                Err(CourseError::Overflow(2))
+            ))
+        );
+        assert_eq!(
+            CourseError::Overflow(2).to_string(),
+            "command 3 overflows the course"
+        );
+        assert_eq!(
+            CourseError::NegativeDepth(1).to_string(),
+            "command 2 takes the depth above the surface"
+        );
     }
 }
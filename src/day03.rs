@@ -1,31 +1,138 @@
-use std::str;
+use std::{cmp, ops, str};
 
 advent_of_code::day!(03);
 
-#[derive(Debug)]
+/// An unsigned integer of any size, just big enough for the ratings of arbitrarily wide lines.
+/// The limbs are little-endian and never end in a zero, so equal values compare equal.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    /// Reads the bits most significant first.
+    pub fn from_bits<I>(bits: I) -> Self
+    where
+        I: IntoIterator<Item = bool>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        let mut limbs = Vec::new();
+        for (idx, bit) in bits.into_iter().rev().enumerate() {
+            if idx % 32 == 0 {
+                limbs.push(0);
+            }
+            if bit {
+                limbs[idx / 32] |= 1 << (idx % 32);
+            }
+        }
+
+        Self::normalised(limbs)
+    }
+
+    fn normalised(mut limbs: Vec<u32>) -> Self {
+        let len = limbs.iter().rposition(|&l| l != 0).map_or(0, |idx| idx + 1);
+        limbs.truncate(len);
+
+        Self { limbs }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Divides in place, returning the remainder.
+    fn div_rem(&mut self, divisor: u32) -> u32 {
+        let rem = self.limbs.iter_mut().rev().fold(0u64, |rem, limb| {
+            let cur = rem << 32 | *limb as u64;
+            *limb = (cur / divisor as u64) as u32;
+            cur % divisor as u64
+        });
+        *self = Self::normalised(std::mem::take(&mut self.limbs));
+
+        rem as u32
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        Self::normalised(vec![n as u32, (n >> 32) as u32])
+    }
+}
+
+impl ops::Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: Self) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + rhs.limbs.len()];
+
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let carry = rhs.limbs.iter().enumerate().fold(0u64, |carry, (j, &b)| {
+                let cur = a as u64 * b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = cur as u32;
+                cur >> 32
+            });
+            limbs[i + rhs.limbs.len()] = carry as u32;
+        }
+
+        BigUint::normalised(limbs)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        let mut n = self.clone();
+        let mut chunks = Vec::new();
+        while !n.is_zero() {
+            chunks.push(n.div_rem(1_000_000_000));
+        }
+
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().unwrap())?;
+        chunks.try_for_each(|c| write!(f, "{:09}", c))
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl advent_of_code::submit::Answer for BigUint {
+    fn answer(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+/// A diagnostic line packed 64 bits to a word, most significant bit first, so comparing the
+/// words of two lines of the same width compares their values.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Line {
-    inner: Vec<bool>,
+    words: Vec<u64>,
+    width: usize,
 }
 
 impl Line {
     fn width(&self) -> usize {
-        self.inner.len()
+        self.width
     }
 
-    fn invert(&mut self) {
-        self.inner.iter_mut().for_each(|x| *x = !*x);
+    fn bit(&self, idx: usize) -> bool {
+        self.words[idx / 64] >> (63 - idx % 64) & 1 == 1
     }
 
-    fn to_decimal(&self) -> u32 {
-        self.inner
-            .iter()
-            .rev()
-            .enumerate()
-            .map(|(idx, bit)| match bit {
-                true => 2u32.pow(idx as u32),
-                false => 0,
-            })
-            .sum()
+    pub fn to_decimal(&self) -> BigUint {
+        BigUint::from_bits((0..self.width).map(|i| self.bit(i)))
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (0..self.width).try_for_each(|i| write!(f, "{}", self.bit(i) as u8))
     }
 }
 
@@ -33,90 +140,203 @@ impl str::FromStr for Line {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let inner = s
-            .bytes()
-            .map(|c| match c {
-                b'0' => Ok(false),
-                b'1' => Ok(true),
-                _ => Err(ParseError::UnknownChar(c)),
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let mut words = vec![0; s.len().div_ceil(64)];
 
-        Ok(Self { inner })
+        for (idx, c) in s.bytes().enumerate() {
+            match c {
+                b'0' => {}
+                b'1' => words[idx / 64] |= 1 << (63 - idx % 64),
+                _ => return Err(ParseError::UnknownChar(c)),
+            }
+        }
+
+        Ok(Self {
+            words,
+            width: s.len(),
+        })
     }
 }
 
-impl<'a> advent_of_code::Solution<'a> for Day03 {
-    type Input = Vec<Line>;
-    type ParseError = ParseError;
+/// The report kept two ways: the lines sorted, so the lines sharing a prefix are always
+/// contiguous, and one bitset per column, so counting the ones in a column is a popcount.
+#[derive(Debug)]
+pub struct Diagnostics {
+    width: usize,
+    lines: Vec<Line>,
+    columns: Vec<Vec<u64>>,
+}
 
-    type P1 = Option<u32>;
-    type P2 = Option<u32>;
+impl Diagnostics {
+    pub fn new(mut lines: Vec<Line>) -> Self {
+        let width = lines.first().map_or(0, Line::width);
+        let mut columns = vec![vec![0u64; lines.len().div_ceil(64)]; width];
 
-    fn parse(input: &'a str) -> Result<Self::Input, Self::ParseError> {
-        input.lines().map(str::parse).collect()
+        for (row, line) in lines.iter().enumerate() {
+            for (col, column) in columns.iter_mut().enumerate() {
+                column[row / 64] |= (line.bit(col) as u64) << (row % 64);
+            }
+        }
+        lines.sort_unstable();
+
+        Self {
+            width,
+            lines,
+            columns,
+        }
     }
 
-    fn part1(input: &[Line]) -> Self::P1 {
-        let width = input.first()?.width();
-        let half = input.len() / 2;
+    fn ones(&self, col: usize) -> usize {
+        self.columns[col]
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
 
-        let mut gamma_rate = Line {
-            inner: (0..width)
-                .map(|i| input.iter().filter(|d| d.inner[i]).count() > half)
-                .collect(),
-        };
+    /// Filters the lines one bit position at a time, keeping those whose bit matches the
+    /// criteria, until a single line survives. Returns `None` if none or several survive.
+    pub fn rating(&self, criteria: BitCriteria) -> Option<Survivor<'_>> {
+        let mut range = 0..self.lines.len();
+        let mut trace = Vec::new();
 
-        let gamma = gamma_rate.to_decimal();
-        gamma_rate.invert();
-        let epsilon = gamma_rate.to_decimal();
+        for bit_pos in 0..self.width {
+            if range.len() <= 1 {
+                break;
+            }
 
-        Some(gamma * epsilon)
-    }
+            // Every line in the range shares the bits before `bit_pos`, so the ones sort last.
+            let split =
+                range.start + self.lines[range.clone()].partition_point(|l| !l.bit(bit_pos));
+            let (zeros, ones) = (split - range.start, range.end - split);
+            let kept = criteria.select(ones, zeros);
+
+            range = match kept {
+                true => split..range.end,
+                false => range.start..split,
+            };
+            trace.push(Elimination {
+                bit_pos,
+                ones,
+                zeros,
+                kept,
+                remaining: range.len(),
+            });
+        }
 
-    fn part2(input: &[Line]) -> Self::P2 {
-        #[derive(Debug, Clone, Copy)]
-        enum Rating {
-            O2,
-            CO2,
+        match range.len() {
+            1 => Some(Survivor {
+                line: &self.lines[range.start],
+                trace,
+            }),
+            _ => None,
         }
+    }
+}
 
-        fn filter_records(diagnostics: &mut Vec<&Line>, rating: Rating, bit_pos: usize) {
-            let half = diagnostics.len();
-            let bits = diagnostics.iter().filter(|d| d.inner[bit_pos]).count();
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Selection {
+    Majority,
+    Minority,
+}
 
-            let bit = match rating {
-                Rating::O2 => bits * 2 >= half,
-                Rating::CO2 => bits * 2 < half,
-            };
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TieBreak {
+    Zero,
+    One,
+}
+
+/// Which bit to keep at each position: the most or least common one, with `tie` deciding when
+/// both are equally common.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitCriteria {
+    pub selection: Selection,
+    pub tie: TieBreak,
+}
+
+impl BitCriteria {
+    pub const OXYGEN: Self = Self {
+        selection: Selection::Majority,
+        tie: TieBreak::One,
+    };
 
-            diagnostics.retain(|l| l.inner[bit_pos] == bit);
+    pub const CO2: Self = Self {
+        selection: Selection::Minority,
+        tie: TieBreak::Zero,
+    };
+
+    fn select(self, ones: usize, zeros: usize) -> bool {
+        match (ones.cmp(&zeros), self.selection) {
+            (cmp::Ordering::Equal, _) => self.tie == TieBreak::One,
+            (ord, Selection::Majority) => ord == cmp::Ordering::Greater,
+            (ord, Selection::Minority) => ord == cmp::Ordering::Less,
         }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Elimination {
+    pub bit_pos: usize,
+    pub ones: usize,
+    pub zeros: usize,
+    pub kept: bool,
+    pub remaining: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Survivor<'a> {
+    pub line: &'a Line,
+    pub trace: Vec<Elimination>,
+}
 
-        fn get_rating(diagnostics: &[Line], rating: Rating) -> Option<u32> {
-            let width = diagnostics.first()?.width();
-            let mut diagnostics = diagnostics.iter().collect();
+impl<'a> advent_of_code::Solution<'a> for Day03 {
+    type Input = Box<Diagnostics>;
+    type ParseError = ParseError;
 
-            (0..width).find_map(|i| {
-                filter_records(&mut diagnostics, rating, i);
+    type P1 = Option<BigUint>;
+    type P2 = Option<BigUint>;
 
-                match diagnostics.first() {
-                    Some(l) if diagnostics.len() == 1 => Some(l.to_decimal()),
-                    _ => None,
-                }
-            })
+    fn parse(input: &'a str) -> Result<Self::Input, Self::ParseError> {
+        let lines = input
+            .lines()
+            .map(str::parse)
+            .collect::<Result<Vec<Line>, _>>()?;
+
+        let width = lines.first().map_or(0, Line::width);
+        match lines.iter().position(|l| l.width() != width) {
+            Some(idx) => Err(ParseError::MismatchedWidth(
+                idx + 1,
+                width,
+                lines[idx].width(),
+            )),
+            None => Ok(Box::new(Diagnostics::new(lines))),
         }
+    }
 
-        let o2 = get_rating(input, Rating::O2)?;
-        let co2 = get_rating(input, Rating::CO2)?;
+    fn part1(input: &Diagnostics) -> Self::P1 {
+        input.lines.first()?;
+        let half = input.lines.len() / 2;
 
-        Some(o2 * co2)
+        let gamma_rate = (0..input.width)
+            .map(|i| input.ones(i) > half)
+            .collect::<Vec<_>>();
+
+        let gamma = BigUint::from_bits(gamma_rate.iter().copied());
+        let epsilon = BigUint::from_bits(gamma_rate.iter().map(|b| !b));
+
+        Some(&gamma * &epsilon)
+    }
+
+    fn part2(input: &Diagnostics) -> Self::P2 {
+        let o2 = input.rating(BitCriteria::OXYGEN)?.line.to_decimal();
+        let co2 = input.rating(BitCriteria::CO2)?.line.to_decimal();
+
+        Some(&o2 * &co2)
     }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum ParseError {
     UnknownChar(u8),
+    MismatchedWidth(usize, usize, usize),
 }
 
 use std::{error, fmt};
@@ -125,6 +345,11 @@ impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::UnknownChar(char) => write!(f, "unknown char \"{}\"", *char as char),
+            Self::MismatchedWidth(line, expected, found) => write!(
+                f,
+                "line {} is {} bits wide instead of {}",
+                line, found, expected
+            ),
         }
     }
 }
@@ -150,6 +375,71 @@ mod tests {
 
     #[test]
     fn test() {
-        assert_eq!(super::Day03::solve(INPUT), Ok((Some(198), Some(230))));
+        assert_eq!(
+            super::Day03::solve(INPUT),
+            Ok((Some(198.into()), Some(230.into())))
+        );
+    }
+
+    #[test]
+    fn test_criteria() {
+        use super::{BitCriteria, Elimination, Selection, TieBreak};
+
+        let diagnostics = super::Day03::parse(INPUT).unwrap();
+
+        let o2 = diagnostics.rating(BitCriteria::OXYGEN).unwrap();
+        assert_eq!(o2.line.to_string(), "10111");
+        assert_eq!(o2.trace.len(), 5);
+        assert_eq!(
+            o2.trace[0],
+            Elimination {
+                bit_pos: 0,
+                ones: 7,
+                zeros: 5,
+                kept: true,
+                remaining: 7
+            }
+        );
+        assert_eq!(o2.trace[4].ones, o2.trace[4].zeros);
+
+        let co2 = diagnostics.rating(BitCriteria::CO2).unwrap();
+        assert_eq!(co2.line.to_string(), "01010");
+        assert_eq!(co2.trace.len(), 3);
+
+        let o2_low_ties = BitCriteria {
+            selection: Selection::Majority,
+            tie: TieBreak::Zero,
+        };
+        let survivor = diagnostics.rating(o2_low_ties).unwrap();
+        assert_eq!(survivor.line.to_string(), "10110");
+        assert_eq!(survivor.trace[..4], o2.trace[..4]);
+    }
+
+    #[test]
+    fn test_wide() {
+        use super::{BigUint, ParseError};
+
+        let wide = INPUT
+            .lines()
+            .map(|l| l.repeat(30))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (gamma, o2) = ("10110".repeat(30), "10111".repeat(30));
+        let (epsilon, co2) = ("01001".repeat(30), "01010".repeat(30));
+        let value = |bits: &str| BigUint::from_bits(bits.bytes().map(|b| b == b'1'));
+
+        let (p1, p2) = super::Day03::solve(&wide).unwrap();
+        assert_eq!(p1, Some(&value(&gamma) * &value(&epsilon)));
+        assert_eq!(p2, Some(&value(&o2) * &value(&co2)));
+        assert_eq!(value(&"1".repeat(64)).to_string(), u64::MAX.to_string());
+        assert_eq!(
+            (&BigUint::from(u64::MAX) * &BigUint::from(u64::MAX)).to_string(),
+            (u64::MAX as u128 * u64::MAX as u128).to_string()
+        );
+
+        assert_eq!(
+            super::Day03::parse("0101\n011\n1100").unwrap_err(),
+            ParseError::MismatchedWidth(2, 4, 3)
+        );
     }
 }
@@ -1,95 +1,407 @@
-use std::{cell::Cell, num::ParseIntError, str};
+use std::{collections::VecDeque, fmt::Write, iter, num::ParseIntError, slice, str};
 
 advent_of_code::day!(04);
 
-const SIDE_LEN: usize = 5;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Square {
+    pub value: u8,
+    pub marked: bool,
+}
+
+type Mask = Box<[u64]>;
+
+fn mask<I: IntoIterator<Item = usize>>(len: usize, squares: I) -> Mask {
+    let mut mask = vec![0; len.div_ceil(64)].into_boxed_slice();
+    squares
+        .into_iter()
+        .for_each(|pos| mask[pos / 64] |= 1 << (pos % 64));
+
+    mask
+}
 
+/// A square board whose side length is only known once it has been parsed. The values are
+/// stored row by row, with the marked squares as a bitmask in the same order and an index from
+/// every number to its positions, so a draw only touches the squares it marks.
 #[derive(Debug, Clone)]
-struct Square {
-    value: Cell<u8>,
-    marked: Cell<bool>,
+pub struct Board {
+    side: usize,
+    values: Box<[u8]>,
+    index: Box<[(u8, u32)]>,
+    marked: Mask,
 }
 
-impl Square {
-    fn new(n: u8) -> Self {
+impl Board {
+    fn new(side: usize, values: Vec<u8>) -> Self {
+        let mut index = values
+            .iter()
+            .enumerate()
+            .map(|(pos, &n)| (n, pos as u32))
+            .collect::<Vec<_>>();
+        index.sort_unstable();
+
         Self {
-            value: Cell::new(n),
-            marked: Cell::new(false),
+            side,
+            marked: mask(values.len(), []),
+            values: values.into_boxed_slice(),
+            index: index.into_boxed_slice(),
         }
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct Board<const LEN: usize> {
-    inner: [[Square; LEN]; LEN],
-}
+    pub fn side(&self) -> usize {
+        self.side
+    }
 
-impl<const LEN: usize> Board<LEN> {
-    fn draw(&self, num: u8) {
-        self.inner
-            .iter()
-            .flatten()
-            .filter(|c| c.value.get() == num)
-            .for_each(|c| c.marked.set(true));
+    pub fn squares(&self) -> impl Iterator<Item = Square> + '_ {
+        self.values.iter().enumerate().map(|(pos, &value)| Square {
+            value,
+            marked: self.marked[pos / 64] >> (pos % 64) & 1 == 1,
+        })
+    }
+
+    /// Returns whether any square was marked.
+    fn draw(&mut self, num: u8) -> bool {
+        let start = self.index.partition_point(|&(n, _)| n < num);
+        let mut hit = false;
+
+        for &(_, pos) in self.index[start..].iter().take_while(|&&(n, _)| n == num) {
+            self.marked[pos as usize / 64] |= 1 << (pos % 64);
+            hit = true;
+        }
+
+        hit
     }
 
     fn sum_unmarked(&self) -> u32 {
-        self.inner
-            .iter()
-            .flatten()
-            .filter(|c| !c.marked.get())
-            .map(|c| c.value.get() as u32)
+        self.squares()
+            .filter(|c| !c.marked)
+            .map(|c| c.value as u32)
             .sum()
     }
 
-    fn has_won(&self) -> bool {
-        let any_row = self
-            .inner
+    /// The lines are checked in order, so a draw completing several reports the first.
+    fn winning_line(&self, lines: &WinLines) -> Option<WinLine> {
+        lines
+            .lines
+            .iter()
+            .find(|(_, mask)| {
+                mask.iter()
+                    .zip(self.marked.iter())
+                    .all(|(m, marked)| m & marked == *m)
+            })
+            .map(|&(line, _)| line)
+    }
+
+    pub fn render(&self, style: Style) -> String {
+        let width = self
+            .values
             .iter()
-            .any(|row| row.iter().all(|c| c.marked.get()));
-        let any_col = (0..SIDE_LEN).any(|col| {
-            self.inner
+            .map(|n| n.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let squares = self.squares().collect::<Vec<_>>();
+        let mut out = String::new();
+
+        for row in squares.chunks(self.side.max(1)) {
+            let squares = row
                 .iter()
-                .map(|row| &row[col])
-                .all(|c| c.marked.get())
-        });
+                .map(|c| match (style, c.marked) {
+                    (Style::Brackets, true) => format!("[{:>w$}]", c.value, w = width),
+                    (Style::Brackets, false) => format!(" {:>w$} ", c.value, w = width),
+                    (Style::Ansi, true) => format!("\x1b[1;32m{:>w$}\x1b[0m", c.value, w = width),
+                    (Style::Ansi, false) => format!("{:>w$}", c.value, w = width),
+                })
+                .collect::<Vec<_>>();
+
+            writeln!(out, "{}", squares.join(" ")).unwrap();
+        }
+
+        out
+    }
+
+    fn score(&mut self, num: u8, lines: &WinLines) -> Option<(WinLine, u32)> {
+        if !self.draw(num) {
+            return None;
+        }
+
+        self.winning_line(lines)
+            .map(|line| (line, self.sum_unmarked() * num as u32))
+    }
+}
+
+/// The masks of the squares each win rule needs marked, built once for boards of one side
+/// length and checked in the order of the rules.
+#[derive(Debug)]
+pub struct WinLines {
+    lines: Vec<(WinLine, Mask)>,
+}
+
+impl WinLines {
+    pub fn new(side: usize, rules: &[WinRule]) -> Self {
+        let n = side;
+        let len = n * n;
+        let mut lines = Vec::new();
+
+        for rule in rules.iter().filter(|_| n > 0) {
+            match rule {
+                WinRule::Rows => lines.extend((0..n).map(|row| {
+                    (
+                        WinLine::Row(row),
+                        mask(len, (0..n).map(|col| row * n + col)),
+                    )
+                })),
+                WinRule::Columns => lines.extend((0..n).map(|col| {
+                    (
+                        WinLine::Column(col),
+                        mask(len, (0..n).map(|row| row * n + col)),
+                    )
+                })),
+                WinRule::Diagonals => {
+                    lines.push((WinLine::Diagonal, mask(len, (0..n).map(|i| i * n + i))));
+                    lines.push((
+                        WinLine::AntiDiagonal,
+                        mask(len, (0..n).map(|i| i * n + n - 1 - i)),
+                    ));
+                }
+                WinRule::Corners => lines.push((
+                    WinLine::Corners,
+                    mask(len, [0, n - 1, n * (n - 1), n * n - 1]),
+                )),
+                WinRule::Blackout => lines.push((WinLine::Blackout, mask(len, 0..len))),
+            }
+        }
 
-        any_row || any_col
+        Self { lines }
     }
+}
+
+/// How `Board::render` highlights marked squares: in bold green, or between brackets for
+/// output that won't reach a terminal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    Ansi,
+    Brackets,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WinRule {
+    Rows,
+    Columns,
+    Diagonals,
+    Corners,
+    Blackout,
+}
+
+impl WinRule {
+    pub const CLASSIC: &'static [Self] = &[Self::Rows, Self::Columns];
+}
+
+impl str::FromStr for WinRule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rows" => Ok(Self::Rows),
+            "columns" => Ok(Self::Columns),
+            "diagonals" => Ok(Self::Diagonals),
+            "corners" => Ok(Self::Corners),
+            "blackout" => Ok(Self::Blackout),
+            _ => Err(ParseError::UnknownRule(String::from(s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WinLine {
+    Row(usize),
+    Column(usize),
+    Diagonal,
+    AntiDiagonal,
+    Corners,
+    Blackout,
+}
 
-    fn score(&self, num: u8) -> Option<u32> {
-        self.draw(num);
-        if self.has_won() {
-            Some(self.sum_unmarked() * num as u32)
-        } else {
-            None
+impl fmt::Display for WinLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Row(idx) => write!(f, "row {}", idx),
+            Self::Column(idx) => write!(f, "column {}", idx),
+            Self::Diagonal => write!(f, "the diagonal"),
+            Self::AntiDiagonal => write!(f, "the anti-diagonal"),
+            Self::Corners => write!(f, "the four corners"),
+            Self::Blackout => write!(f, "a blackout"),
         }
     }
 }
 
-impl<const LEN: usize> str::FromStr for Board<LEN> {
+impl str::FromStr for Board {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let inner = s
+        let rows = s
             .split('\n')
             .map(|row| {
                 row.split_ascii_whitespace()
-                    .map(|n| n.parse().map(Square::new).map_err(ParseError::from))
-                    .collect::<Result<Vec<_>, _>>()
-                    .and_then(|vec| <[_; LEN]>::try_from(vec).map_err(ParseError::from))
+                    .map(str::parse)
+                    .collect::<Result<Vec<u8>, _>>()
             })
-            .collect::<Result<Vec<_>, _>>()
-            .and_then(|vec| <[_; LEN]>::try_from(vec).map_err(ParseError::from))?;
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(Self { inner })
+        let side = rows.len();
+        match rows.iter().find(|row| row.len() != side) {
+            Some(row) => Err(ParseError::WrongSideLen(side, row.len())),
+            None => Ok(Self::new(side, rows.into_iter().flatten().collect())),
+        }
     }
 }
 
-type Draw = (u8, Box<[Board<SIDE_LEN>]>);
+#[derive(Debug)]
+pub struct Bingo {
+    boards: Vec<Board>,
+    draws: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Win {
+    pub board: usize,
+    pub draw: usize,
+    pub score: u32,
+    pub line: WinLine,
+}
+
+/// Plays the draws one at a time on fresh copies of the boards, yielding a win event for every
+/// board the moment it wins. Boards winning on the same draw come out in board order.
+#[derive(Debug)]
+pub struct BingoGame<'a> {
+    lines: WinLines,
+    boards: Vec<Board>,
+    won: Vec<bool>,
+    draws: iter::Enumerate<slice::Iter<'a, u8>>,
+    pending: VecDeque<Win>,
+}
+
+impl<'a> BingoGame<'a> {
+    pub fn new(bingo: &'a Bingo) -> Self {
+        Self::with_rules(bingo, WinRule::CLASSIC)
+    }
+
+    pub fn with_rules(bingo: &'a Bingo, rules: &'a [WinRule]) -> Self {
+        let side = bingo.boards.first().map_or(0, Board::side);
+
+        Self {
+            lines: WinLines::new(side, rules),
+            boards: bingo.boards.clone(),
+            won: vec![false; bingo.boards.len()],
+            draws: bingo.draws.iter().enumerate(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl Iterator for BingoGame<'_> {
+    type Item = Win;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            let (draw, &num) = self.draws.next()?;
+
+            for (board, (b, won)) in self.boards.iter_mut().zip(&mut self.won).enumerate() {
+                if *won {
+                    continue;
+                }
+
+                if let Some((line, score)) = b.score(num, &self.lines) {
+                    *won = true;
+                    self.pending.push_back(Win {
+                        board,
+                        draw,
+                        score,
+                        line,
+                    });
+                }
+            }
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+/// Replays the draws on fresh copies of the boards, yielding one rendered frame of every
+/// board per draw.
+#[derive(Debug)]
+pub struct Replay<'a> {
+    boards: Vec<Board>,
+    draws: iter::Enumerate<slice::Iter<'a, u8>>,
+    style: Style,
+}
+
+pub fn replay(bingo: &Bingo, style: Style) -> Replay<'_> {
+    Replay {
+        boards: bingo.boards.clone(),
+        draws: bingo.draws.iter().enumerate(),
+        style,
+    }
+}
+
+impl Iterator for Replay<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (draw, &num) = self.draws.next()?;
+        let mut frame = format!("draw {}: {}\n", draw, num);
+
+        for (idx, board) in self.boards.iter_mut().enumerate() {
+            board.draw(num);
+            write!(frame, "\nboard {}\n{}", idx, board.render(self.style)).unwrap();
+        }
+
+        Some(frame)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Place {
+    pub rank: usize,
+    pub win: Win,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Ranking {
+    pub places: Vec<Place>,
+    pub never_won: Vec<usize>,
+}
+
+impl Ranking {
+    pub fn is_tied(&self, place: &Place) -> bool {
+        self.places
+            .iter()
+            .filter(|p| p.rank == place.rank)
+            .nth(1)
+            .is_some()
+    }
+}
+
+/// Ranks every board by the draw it wins on. Boards winning on the same draw tie: they share a
+/// rank, are listed by board index, and the next rank skips past them as in "1, 2, 2, 4".
+pub fn ranking(bingo: &Bingo, rules: &[WinRule]) -> Ranking {
+    let mut places = Vec::<Place>::new();
+
+    for (idx, win) in BingoGame::with_rules(bingo, rules).enumerate() {
+        let rank = match places.last() {
+            Some(prev) if prev.win.draw == win.draw => prev.rank,
+            _ => idx + 1,
+        };
+        places.push(Place { rank, win });
+    }
+
+    let mut won = vec![false; bingo.boards.len()];
+    places.iter().for_each(|p| won[p.win.board] = true);
+    let never_won = (0..won.len()).filter(|&b| !won[b]).collect();
+
+    Ranking { places, never_won }
+}
 
 impl<'a> advent_of_code::Solution<'a> for Day04 {
-    type Input = Vec<Draw>;
+    type Input = Box<Bingo>;
     type ParseError = ParseError;
 
     type P1 = Option<u32>;
@@ -101,46 +413,36 @@ impl<'a> advent_of_code::Solution<'a> for Day04 {
             .split_once("\n\n")
             .ok_or(ParseError::MissingDrawNumbers)?;
 
-        let boards = boards
-            .split("\n\n")
-            .map(str::parse)
-            .collect::<Result<Vec<Board<SIDE_LEN>>, _>>()?;
+        let boards = {
+            advent_of_code::span!("boards");
+
+            boards
+                .split("\n\n")
+                .map(str::parse)
+                .collect::<Result<Vec<Board>, _>>()?
+        };
+
+        let side = boards.first().map_or(0, Board::side);
+        if let Some(idx) = boards.iter().position(|b| b.side() != side) {
+            return Err(ParseError::MismatchedSize(idx, side, boards[idx].side()));
+        }
+
+        advent_of_code::span!("draws");
 
         let draws = draws
             .split(',')
             .map(str::parse)
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(draws
-            .iter()
-            .map(|&num| {
-                boards.iter().for_each(|b| b.draw(num));
+        Ok(Box::new(Bingo { boards, draws }))
+    }
 
-                (num, boards.clone().into_boxed_slice())
-            })
-            .collect())
+    fn part1(input: &Bingo) -> Self::P1 {
+        BingoGame::new(input).next().map(|win| win.score)
     }
 
-    fn part1(input: &[Draw]) -> Self::P1 {
-        input
-            .iter()
-            .find(|(_, boards)| boards.iter().any(Board::has_won))
-            .and_then(|(draw, boards)| boards.iter().find_map(|board| board.score(*draw)))
-    }
-
-    fn part2(input: &[Draw]) -> Self::P2 {
-        input
-            .array_windows::<2>()
-            .find_map(|[(_, previous_boards), (num, boards)]| {
-                if boards.iter().filter(|board| !board.has_won()).count() == 0 {
-                    previous_boards
-                        .iter()
-                        .find(|board| !board.has_won())?
-                        .score(*num)
-                } else {
-                    None
-                }
-            })
+    fn part2(input: &Bingo) -> Self::P2 {
+        BingoGame::new(input).last().map(|win| win.score)
     }
 }
 
@@ -148,7 +450,9 @@ impl<'a> advent_of_code::Solution<'a> for Day04 {
 pub enum ParseError {
     MissingDrawNumbers,
     NotAnInt(ParseIntError),
-    WrongSideLen(usize),
+    WrongSideLen(usize, usize),
+    MismatchedSize(usize, usize, usize),
+    UnknownRule(String),
 }
 
 impl From<ParseIntError> for ParseError {
@@ -157,12 +461,6 @@ impl From<ParseIntError> for ParseError {
     }
 }
 
-impl<T> From<Vec<T>> for ParseError {
-    fn from(vec: Vec<T>) -> Self {
-        Self::WrongSideLen(vec.len())
-    }
-}
-
 use std::{error, fmt};
 
 impl fmt::Display for ParseError {
@@ -170,11 +468,17 @@ impl fmt::Display for ParseError {
         match self {
             Self::MissingDrawNumbers => write!(f, "draw numbers are missing"),
             Self::NotAnInt(parse_int_err) => write!(f, "{}", parse_int_err),
-            Self::WrongSideLen(actual_len) => write!(
+            Self::WrongSideLen(side_len, actual_len) => write!(
                 f,
                 "expected {} as the side length but instead found {}",
-                SIDE_LEN, actual_len
+                side_len, actual_len
             ),
+            Self::MismatchedSize(board, side_len, actual_len) => write!(
+                f,
+                "board {} has side length {} instead of {}",
+                board, actual_len, side_len
+            ),
+            Self::UnknownRule(rule) => write!(f, "unknown win rule \"{}\"", rule),
         }
     }
 }
@@ -216,4 +520,125 @@ mod tests {
     fn test() {
         assert_eq!(super::Day04::solve(INPUT), Ok((Some(4_512), Some(1_924))));
     }
+
+    #[test]
+    fn test_game() {
+        use super::{BingoGame, Win, WinLine};
+
+        let bingo = super::Day04::parse(INPUT).unwrap();
+        let wins = BingoGame::new(&bingo).collect::<Vec<_>>();
+
+        assert_eq!(
+            wins,
+            vec![
+                Win {
+                    board: 2,
+                    draw: 11,
+                    score: 4_512,
+                    line: WinLine::Row(0)
+                },
+                Win {
+                    board: 0,
+                    draw: 13,
+                    score: 2_192,
+                    line: WinLine::Row(2)
+                },
+                Win {
+                    board: 1,
+                    draw: 14,
+                    score: 1_924,
+                    line: WinLine::Column(2)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ranking() {
+        let (_, boards) = INPUT.split_once("\n\n").unwrap();
+        let third = boards.rsplit("\n\n").next().unwrap();
+        let input = format!(
+            "{}\n\n{}\n\n90 91 92 93 94\n95 96 97 98 99\n80 81 82 83 84\n85 86 87 88 89\n70 71 72 73 74",
+            INPUT, third
+        );
+
+        let bingo = super::Day04::parse(&input).unwrap();
+        let ranking = super::ranking(&bingo, super::WinRule::CLASSIC);
+        let places = ranking
+            .places
+            .iter()
+            .map(|p| (p.rank, p.win.board, p.win.draw))
+            .collect::<Vec<_>>();
+
+        assert_eq!(places, vec![(1, 2, 11), (1, 3, 11), (3, 0, 13), (4, 1, 14)]);
+        assert!(ranking.is_tied(&ranking.places[1]));
+        assert!(!ranking.is_tied(&ranking.places[2]));
+        assert_eq!(ranking.never_won, vec![4]);
+    }
+
+    #[test]
+    fn test_rules() {
+        use super::{BingoGame, ParseError, WinLine, WinRule};
+
+        const SMALL: &str = "1,5,9,3,7,2
+
+1 2 3
+4 5 6
+7 8 9
+
+9 8 7
+6 5 4
+3 2 1";
+
+        let bingo = super::Day04::parse(SMALL).unwrap();
+        let wins = |rules| {
+            BingoGame::with_rules(&bingo, rules)
+                .map(|w| (w.board, w.draw, w.line, w.score))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            wins(WinRule::CLASSIC),
+            vec![(0, 5, WinLine::Row(0), 36), (1, 5, WinLine::Row(2), 36)]
+        );
+        assert_eq!(
+            wins(&[WinRule::Diagonals]),
+            vec![
+                (0, 2, WinLine::Diagonal, 270),
+                (1, 2, WinLine::Diagonal, 270)
+            ]
+        );
+        assert_eq!(
+            wins(&[WinRule::Corners, WinRule::Blackout]),
+            vec![(0, 4, WinLine::Corners, 140), (1, 4, WinLine::Corners, 140)]
+        );
+        assert!(wins(&[WinRule::Blackout]).is_empty());
+
+        let mixed = format!("{}\n\n{}", INPUT, SMALL.split_once("\n\n").unwrap().1);
+        assert_eq!(
+            super::Day04::parse(&mixed).unwrap_err(),
+            ParseError::MismatchedSize(3, 5, 3)
+        );
+        assert_eq!(
+            super::Day04::parse("1\n\n1 2\n3").unwrap_err(),
+            ParseError::WrongSideLen(2, 1)
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        use super::Style;
+
+        let bingo = super::Day04::parse("5,1\n\n1 12\n5 3").unwrap();
+        let frames = super::replay(&bingo, Style::Brackets).collect::<Vec<_>>();
+
+        assert_eq!(
+            frames,
+            vec![
+                "draw 0: 5\n\nboard 0\n  1   12 \n[ 5]   3 \n",
+                "draw 1: 1\n\nboard 0\n[ 1]  12 \n[ 5]   3 \n"
+            ]
+        );
+        assert_eq!(bingo.boards[0].render(Style::Ansi), " 1 12\n 5  3\n");
+    }
 }
@@ -1,27 +1,18 @@
-use std::{collections::HashMap, num::ParseIntError, ops, str};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io,
+    num::ParseIntError,
+    str,
+};
+
+use advent_of_code::{Variant, Variants};
 
 advent_of_code::day!(05);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-type Step = Point;
-
-impl ops::AddAssign for Point {
-    fn add_assign(&mut self, rhs: Self) {
-        self.x += rhs.x;
-        self.y += rhs.y;
-    }
-}
-
-impl ops::SubAssign for Point {
-    fn sub_assign(&mut self, rhs: Self) {
-        self.x -= rhs.x;
-        self.y -= rhs.y;
-    }
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -38,6 +29,70 @@ impl Line {
     fn points(&self) -> Points {
         Points::new(*self)
     }
+
+    /// The offset from start to end, widened so that it can't overflow.
+    fn delta(&self) -> (i64, i64) {
+        (
+            self.end.x as i64 - self.start.x as i64,
+            self.end.y as i64 - self.start.y as i64,
+        )
+    }
+
+    fn is_octilinear(&self) -> bool {
+        let (dx, dy) = self.delta();
+
+        dx == 0 || dy == 0 || dx.abs() == dy.abs()
+    }
+
+    /// Splits the line into spans along the axes. Lines at other slopes have no axis to run
+    /// along, so each of their points becomes a span of its own.
+    fn spans(&self) -> Vec<(Axis, Span)> {
+        if self.is_octilinear() {
+            vec![self.span()]
+        } else {
+            self.points()
+                .map(|p| {
+                    (
+                        Axis::Horizontal,
+                        Span {
+                            key: p.y as i64,
+                            lo: p.x as i64,
+                            hi: p.x as i64,
+                        },
+                    )
+                })
+                .collect()
+        }
+    }
+
+    /// Assumes the line is horizontal, vertical or at 45°.
+    fn span(&self) -> (Axis, Span) {
+        let (start, end) = (
+            (self.start.x as i64, self.start.y as i64),
+            (self.end.x as i64, self.end.y as i64),
+        );
+        let axis = if start.1 == end.1 {
+            Axis::Horizontal
+        } else if start.0 == end.0 {
+            Axis::Vertical
+        } else if end.0 - start.0 == end.1 - start.1 {
+            Axis::Diagonal
+        } else {
+            Axis::AntiDiagonal
+        };
+
+        let (key, a) = axis.coords(start);
+        let (_, b) = axis.coords(end);
+
+        (
+            axis,
+            Span {
+                key,
+                lo: i64::min(a, b),
+                hi: i64::max(a, b),
+            },
+        )
+    }
 }
 
 impl str::FromStr for Line {
@@ -70,21 +125,32 @@ impl str::FromStr for Line {
     }
 }
 
+/// Walks a line with Bresenham's algorithm, which ends on `end` whatever the slope. On
+/// horizontal, vertical and 45° lines that's every point between the ends.
 #[derive(Debug)]
 struct Points {
-    line: Line,
-    step: Step,
+    next: Option<(i64, i64)>,
+    end: (i64, i64),
+    step: (i64, i64),
+    delta: (i64, i64),
+    err: i64,
 }
 
 impl Points {
-    fn new(mut line: Line) -> Self {
-        let step = Step {
-            x: i32::signum(line.end.x - line.start.x),
-            y: i32::signum(line.end.y - line.start.y),
-        };
-        line.start -= step;
-
-        Self { line, step }
+    fn new(line: Line) -> Self {
+        let (start, end) = (
+            (line.start.x as i64, line.start.y as i64),
+            (line.end.x as i64, line.end.y as i64),
+        );
+        let delta = ((end.0 - start.0).abs(), -(end.1 - start.1).abs());
+
+        Self {
+            next: Some(start),
+            end,
+            step: ((end.0 - start.0).signum(), (end.1 - start.1).signum()),
+            delta,
+            err: delta.0 + delta.1,
+        }
     }
 }
 
@@ -92,14 +158,28 @@ impl Iterator for Points {
     type Item = Point;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.line.start == self.line.end {
+        let (x, y) = self.next?;
+
+        self.next = if (x, y) == self.end {
             None
         } else {
-            self.line.start.x += self.step.x;
-            self.line.start.y += self.step.y;
+            let (mut x, mut y) = (x, y);
+            let err = 2 * self.err;
+            if err >= self.delta.1 {
+                self.err += self.delta.1;
+                x += self.step.0;
+            }
+            if err <= self.delta.0 {
+                self.err += self.delta.0;
+                y += self.step.1;
+            }
+            Some((x, y))
+        };
 
-            Some(self.line.start)
-        }
+        Some(Point {
+            x: x as i32,
+            y: y as i32,
+        })
     }
 }
 
@@ -117,6 +197,481 @@ where
         .count()
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Axis {
+    Horizontal,
+    Vertical,
+    Diagonal,
+    AntiDiagonal,
+}
+
+impl Axis {
+    const ALL: [Self; 4] = [
+        Self::Horizontal,
+        Self::Vertical,
+        Self::Diagonal,
+        Self::AntiDiagonal,
+    ];
+
+    /// Splits a point into the coordinate that's constant along lines of this axis and the one
+    /// that varies. Diagonals vary in x, so consecutive points differ by one either way.
+    fn coords(self, (x, y): (i64, i64)) -> (i64, i64) {
+        match self {
+            Self::Horizontal => (y, x),
+            Self::Vertical => (x, y),
+            Self::Diagonal => (x - y, x),
+            Self::AntiDiagonal => (x + y, x),
+        }
+    }
+}
+
+/// The points `lo..=hi` of the line of an axis with constant coordinate `key`.
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    key: i64,
+    lo: i64,
+    hi: i64,
+}
+
+/// The stretches of every line covered by at least two of the spans, sorted per line.
+fn collinear_overlaps(spans: &[Span]) -> HashMap<i64, Vec<(i64, i64)>> {
+    let mut events = spans
+        .iter()
+        .flat_map(|s| [(s.key, s.lo, 1), (s.key, s.hi + 1, -1)])
+        .collect::<Vec<_>>();
+    events.sort_unstable();
+
+    let mut overlaps = HashMap::<_, Vec<_>>::new();
+    let (mut count, mut start) = (0, None);
+    for (idx, &(key, pos, delta)) in events.iter().enumerate() {
+        count += delta;
+
+        // Only look at the coverage once every event at this position has been applied.
+        if matches!(events.get(idx + 1), Some(&(k, p, _)) if (k, p) == (key, pos)) {
+            continue;
+        }
+
+        match (count >= 2, start) {
+            (true, None) => start = Some(pos),
+            (false, Some(lo)) => {
+                overlaps.entry(key).or_default().push((lo, pos - 1));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    overlaps
+}
+
+fn is_covered(overlaps: &[(i64, i64)], at: i64) -> bool {
+    let idx = overlaps.partition_point(|&(_, hi)| hi < at);
+
+    matches!(overlaps.get(idx), Some(&(lo, _)) if lo <= at)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Event {
+    Open,
+    Cross,
+    Close,
+}
+
+/// Sweeps across the columns, reporting every `(col, row)` where a column crosses a row. Rows
+/// open before and close after the columns at the same position, so touching counts.
+fn crossings(rows: &[Span], cols: &[Span]) -> Vec<(i64, i64)> {
+    let mut events = rows
+        .iter()
+        .flat_map(|r| [(r.lo, Event::Open, r), (r.hi, Event::Close, r)])
+        .chain(cols.iter().map(|c| (c.key, Event::Cross, c)))
+        .collect::<Vec<_>>();
+    events.sort_unstable_by_key(|&(pos, event, _)| (pos, event));
+
+    let mut active = BTreeMap::new();
+    let mut out = Vec::new();
+    for (pos, event, span) in events {
+        match event {
+            Event::Open => *active.entry(span.key).or_insert(0) += 1,
+            Event::Cross => out.extend(active.range(span.lo..=span.hi).map(|(&row, _)| (pos, row))),
+            Event::Close => {
+                let open = active.get_mut(&span.key).unwrap();
+                *open -= 1;
+                if *open == 0 {
+                    active.remove(&span.key);
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// Where a horizontal or vertical span meets a diagonal one, if they meet on a point.
+fn meet((axis, straight): (Axis, Span), (diagonal, slanted): (Axis, Span)) -> Option<(i64, i64)> {
+    let (c, k) = (straight.key, slanted.key);
+    let point = match (axis, diagonal) {
+        (Axis::Horizontal, Axis::Diagonal) => (k + c, c),
+        (Axis::Horizontal, _) => (k - c, c),
+        (_, Axis::Diagonal) => (c, c - k),
+        _ => (c, k - c),
+    };
+
+    let (_, a) = axis.coords(point);
+    let (_, b) = diagonal.coords(point);
+    let within = |span: Span, at| (span.lo..=span.hi).contains(&at);
+
+    (within(straight, a) && within(slanted, b)).then_some(point)
+}
+
+/// Counts the points covered by at least two lines without visiting the points themselves.
+/// Those are the stretches where collinear lines overlap plus the points where lines of
+/// different axes cross, so the cost depends on the number of lines and crossings rather than
+/// on how long the lines are. Lines at other slopes still cost a span per point.
+fn sweep_overlaps<'a, I>(iter: I) -> usize
+where
+    I: Iterator<Item = &'a Line>,
+{
+    let mut spans = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+    iter.flat_map(Line::spans)
+        .for_each(|(axis, span)| spans[axis as usize].push(span));
+    let [horizontal, vertical, diagonal, anti_diagonal] = &spans;
+
+    let mut crossed = crossings(horizontal, vertical)
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    // Turned by 45°, with u = x + y and v = x - y, the diagonals are horizontal and vertical.
+    let rotate = |spans: &[Span]| {
+        spans
+            .iter()
+            .map(|s| Span {
+                key: s.key,
+                lo: 2 * s.lo - s.key,
+                hi: 2 * s.hi - s.key,
+            })
+            .collect::<Vec<_>>()
+    };
+    crossed.extend(
+        crossings(&rotate(diagonal), &rotate(anti_diagonal))
+            .into_iter()
+            .filter(|(u, v)| (u + v) % 2 == 0)
+            .map(|(u, v)| ((u + v) / 2, (u - v) / 2)),
+    );
+
+    let straight = Axis::ALL[..2]
+        .iter()
+        .flat_map(|&axis| spans[axis as usize].iter().map(move |&s| (axis, s)));
+    for s in straight {
+        let slanted = Axis::ALL[2..]
+            .iter()
+            .flat_map(|&axis| spans[axis as usize].iter().map(move |&s| (axis, s)));
+        crossed.extend(slanted.filter_map(|d| meet(s, d)));
+    }
+
+    let overlaps = spans
+        .iter()
+        .map(|s| collinear_overlaps(s))
+        .collect::<Vec<_>>();
+    let collinear = overlaps
+        .iter()
+        .flat_map(HashMap::values)
+        .flatten()
+        .map(|(lo, hi)| hi - lo + 1)
+        .sum::<i64>();
+
+    // A crossing inside overlaps of several axes was counted once for each of them.
+    let adjustment = crossed
+        .iter()
+        .map(|&p| {
+            let covered = Axis::ALL
+                .iter()
+                .filter(|&&axis| {
+                    let (key, at) = axis.coords(p);
+                    matches!(overlaps[axis as usize].get(&key), Some(o) if is_covered(o, at))
+                })
+                .count() as i64;
+
+            match covered {
+                0 => 1,
+                covered => 1 - covered,
+            }
+        })
+        .sum::<i64>();
+
+    (collinear + adjustment) as usize
+}
+
+/// Past this many cells the dense grid takes more memory than it saves time.
+const DENSE_LIMIT: i64 = 1 << 24;
+
+/// The smallest `(x, y, width, height)` box holding every line.
+fn bounds(lines: &[&Line]) -> Option<(i64, i64, i64, i64)> {
+    let points = || lines.iter().flat_map(|l| [l.start, l.end]);
+    let (min_x, max_x) = (points().map(|p| p.x).min()?, points().map(|p| p.x).max()?);
+    let (min_y, max_y) = (points().map(|p| p.y).min()?, points().map(|p| p.y).max()?);
+
+    Some((
+        min_x as i64,
+        min_y as i64,
+        max_x as i64 - min_x as i64 + 1,
+        max_y as i64 - min_y as i64 + 1,
+    ))
+}
+
+/// Whether the box around the lines is small enough for the dense grid.
+fn fits_dense(lines: &[&Line]) -> bool {
+    let cells = bounds(lines).and_then(|(_, _, width, height)| width.checked_mul(height));
+
+    matches!(cells, Some(cells) if cells <= DENSE_LIMIT)
+}
+
+fn dense_overlaps(lines: &[&Line]) -> usize {
+    let (x, y, width, height) = match bounds(lines) {
+        Some(bounds) if fits_dense(lines) => bounds,
+        Some(_) => return sweep_overlaps(lines.iter().copied()),
+        None => return 0,
+    };
+
+    let mut grid = vec![0u8; (width * height) as usize];
+    for p in lines.iter().flat_map(|l| l.points()) {
+        let cell = &mut grid[((p.y as i64 - y) * width + p.x as i64 - x) as usize];
+        *cell = cell.saturating_add(1);
+    }
+
+    grid.iter().filter(|&&c| c > 1).count()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+    /// Dense when the lines fit in a small enough box, sparse otherwise.
+    Auto,
+    /// Counts every point in a flat grid spanning the lines, or sweeps when the grid would be
+    /// too large to allocate.
+    Dense,
+    /// Counts every point in a map, so only the covered points take memory.
+    Sparse,
+    Sweep,
+}
+
+fn count(lines: &[&Line], method: Method) -> usize {
+    match method {
+        Method::Auto if fits_dense(lines) => {
+            advent_of_code::span!("dense");
+            count(lines, Method::Dense)
+        }
+        Method::Auto => {
+            advent_of_code::span!("sparse");
+            count(lines, Method::Sparse)
+        }
+        Method::Dense => dense_overlaps(lines),
+        Method::Sparse => count_overlaps(lines.iter().copied()),
+        Method::Sweep => sweep_overlaps(lines.iter().copied()),
+    }
+}
+
+pub fn overlaps(lines: &[Line], diagonals: bool, method: Method) -> usize {
+    let lines = lines
+        .iter()
+        .filter(|line| diagonals || !line.is_diagonal())
+        .collect::<Vec<_>>();
+
+    count(&lines, method)
+}
+
+/// Every point covered by the lines along with the lines covering it, for asking about the
+/// vents beyond the puzzle's answers and for drawing them. Lines are referred to by their
+/// index in the slice the map was built from.
+#[derive(Debug)]
+pub struct VentMap {
+    segments: HashMap<Point, Vec<usize>>,
+    x: i64,
+    y: i64,
+    width: i64,
+    height: i64,
+}
+
+impl VentMap {
+    pub fn new(lines: &[Line], diagonals: bool) -> Self {
+        let lines = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| diagonals || !line.is_diagonal())
+            .collect::<Vec<_>>();
+        let (x, y, width, height) =
+            bounds(&lines.iter().map(|&(_, l)| l).collect::<Vec<_>>()).unwrap_or((0, 0, 0, 0));
+
+        let mut segments = HashMap::<_, Vec<_>>::new();
+        for (idx, line) in lines {
+            line.points()
+                .for_each(|p| segments.entry(p).or_default().push(idx));
+        }
+
+        Self {
+            segments,
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> i64 {
+        self.width
+    }
+
+    pub fn height(&self) -> i64 {
+        self.height
+    }
+
+    pub fn count(&self, point: Point) -> usize {
+        self.segments_through(point).len()
+    }
+
+    pub fn segments_through(&self, point: Point) -> &[usize] {
+        self.segments.get(&point).map_or(&[], Vec::as_slice)
+    }
+
+    /// The points covered at least `k` times, row by row.
+    pub fn points_at_least(&self, k: usize) -> Vec<(Point, usize)> {
+        let mut points = self
+            .segments
+            .iter()
+            .map(|(&p, segments)| (p, segments.len()))
+            .filter(|&(_, count)| count >= k.max(1))
+            .collect::<Vec<_>>();
+        points.sort_unstable_by_key(|(p, _)| (p.y, p.x));
+
+        points
+    }
+
+    /// Of the points covered most often, the first one row by row.
+    pub fn most_crossed(&self) -> Option<(Point, usize)> {
+        self.segments
+            .iter()
+            .map(|(&p, segments)| (p, segments.len()))
+            .min_by_key(|&(p, count)| (usize::MAX - count, p.y, p.x))
+    }
+
+    /// The counts row by row over the whole bounding box, with those below `threshold` as 0.
+    fn rows(&self, threshold: usize) -> impl Iterator<Item = Vec<usize>> + '_ {
+        (self.y..self.y + self.height).map(move |y| {
+            (self.x..self.x + self.width)
+                .map(|x| {
+                    let point = Point {
+                        x: x as i32,
+                        y: y as i32,
+                    };
+
+                    match self.count(point) {
+                        count if count >= threshold => count,
+                        _ => 0,
+                    }
+                })
+                .collect()
+        })
+    }
+
+    /// Whether the bounding box is small enough to draw, since drawing visits every cell.
+    pub fn drawable(&self) -> bool {
+        matches!(self.width.checked_mul(self.height), Some(cells) if cells <= DENSE_LIMIT)
+    }
+
+    fn check_drawable(&self) -> Result<(), RenderError> {
+        if self.drawable() {
+            Ok(())
+        } else {
+            Err(RenderError::TooLarge(self.width, self.height))
+        }
+    }
+
+    /// Draws the map like the puzzle statement does, with `+` for more than nine lines.
+    pub fn write_ascii(
+        &self,
+        mut out: impl io::Write,
+        threshold: usize,
+    ) -> Result<(), RenderError> {
+        self.check_drawable()?;
+
+        for row in self.rows(threshold) {
+            let line = row
+                .iter()
+                .map(|&count| match count {
+                    0 => '.',
+                    1..=9 => (b'0' + count as u8) as char,
+                    _ => '+',
+                })
+                .collect::<String>();
+            writeln!(out, "{}", line)?;
+        }
+
+        Ok(())
+    }
+
+    /// A plain PGM image, brighter where more lines cross.
+    pub fn write_pgm(&self, mut out: impl io::Write, threshold: usize) -> Result<(), RenderError> {
+        self.check_drawable()?;
+
+        let max = self
+            .points_at_least(threshold)
+            .iter()
+            .map(|&(_, count)| count)
+            .max()
+            .unwrap_or(1);
+        write!(out, "P2\n{} {}\n{}\n", self.width, self.height, max)?;
+
+        // Plain PGM lines shouldn't be longer than 70 characters.
+        for row in self.rows(threshold) {
+            let mut line = String::new();
+            for count in row {
+                let value = count.to_string();
+                if !line.is_empty() && line.len() + 1 + value.len() > 70 {
+                    writeln!(out, "{}", line)?;
+                    line.clear();
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(&value);
+            }
+            writeln!(out, "{}", line)?;
+        }
+
+        Ok(())
+    }
+
+    /// Only lists the points covered at least `threshold` times, so it works for maps of any
+    /// size.
+    pub fn write_csv(&self, mut out: impl io::Write, threshold: usize) -> Result<(), RenderError> {
+        writeln!(out, "x,y,count")?;
+        for (p, count) in self.points_at_least(threshold) {
+            writeln!(out, "{},{},{}", p.x, p.y, count)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Which lines parsing accepts: only horizontal, vertical and 45° ones as in the puzzle, or
+/// lines at any slope, drawn with Bresenham's algorithm.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Raster {
+    Octilinear,
+    Bresenham,
+}
+
+pub fn parse_lines(input: &str, raster: Raster) -> Result<Vec<Line>, ParseError> {
+    input
+        .lines()
+        .map(|l| match l.parse::<Line>()? {
+            line if raster == Raster::Octilinear && !line.is_octilinear() => {
+                let (dx, dy) = line.delta();
+                Err(ParseError::UnsupportedSlope(dx, dy))
+            }
+            line => Ok(line),
+        })
+        .collect()
+}
+
 impl<'a> advent_of_code::Solution<'a> for Day05 {
     type Input = Vec<Line>;
     type ParseError = ParseError;
@@ -125,15 +680,35 @@ impl<'a> advent_of_code::Solution<'a> for Day05 {
     type P2 = usize;
 
     fn parse(input: &'a str) -> Result<Self::Input, Self::ParseError> {
-        input.lines().map(str::parse).collect()
+        parse_lines(input, Raster::Octilinear)
     }
 
     fn part1(input: &[Line]) -> Self::P1 {
-        count_overlaps(input.iter().filter(|line| !line.is_diagonal()))
+        overlaps(input, false, Method::Auto)
     }
 
     fn part2(input: &[Line]) -> Self::P2 {
-        count_overlaps(input.iter())
+        overlaps(input, true, Method::Auto)
+    }
+
+    fn variants() -> Variants<'a, Self> {
+        vec![
+            Variant {
+                name: "dense",
+                part1: |lines| overlaps(lines, false, Method::Dense),
+                part2: |lines| overlaps(lines, true, Method::Dense),
+            },
+            Variant {
+                name: "sparse",
+                part1: |lines| overlaps(lines, false, Method::Sparse),
+                part2: |lines| overlaps(lines, true, Method::Sparse),
+            },
+            Variant {
+                name: "sweep",
+                part1: |lines| overlaps(lines, false, Method::Sweep),
+                part2: |lines| overlaps(lines, true, Method::Sweep),
+            },
+        ]
     }
 }
 
@@ -142,6 +717,7 @@ pub enum ParseError {
     MissingPointsSeparator,
     MissingCoordsSeparator,
     NotAnInt(ParseIntError),
+    UnsupportedSlope(i64, i64),
 }
 
 impl From<ParseIntError> for ParseError {
@@ -158,6 +734,11 @@ impl fmt::Display for ParseError {
             Self::MissingPointsSeparator => write!(f, "missing points separator"),
             Self::MissingCoordsSeparator => write!(f, "missing coords separator"),
             Self::NotAnInt(parse_int_err) => write!(f, "{}", parse_int_err),
+            Self::UnsupportedSlope(dx, dy) => write!(
+                f,
+                "line with slope {}/{} is neither straight nor at 45°",
+                dy, dx
+            ),
         }
     }
 }
@@ -171,6 +752,38 @@ impl error::Error for ParseError {
     }
 }
 
+#[derive(Debug)]
+pub enum RenderError {
+    TooLarge(i64, i64),
+    Io(io::Error),
+}
+
+impl From<io::Error> for RenderError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge(width, height) => {
+                write!(f, "a {}x{} map is too large to draw", width, height)
+            }
+            Self::Io(io_err) => write!(f, "{}", io_err),
+        }
+    }
+}
+
+impl error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(io_err) => Some(io_err),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use advent_of_code::Solution;
@@ -190,4 +803,189 @@ mod tests {
     fn test() {
         assert_eq!(super::Day05::solve(INPUT), Ok((5, 12)));
     }
+
+    /// Random horizontal, vertical and diagonal lines in a small box, so they overlap a lot.
+    fn generated(count: usize) -> Vec<Vec<super::Line>> {
+        let mut seed = 1u32;
+        let mut next = |n: u32| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 16) % n
+        };
+
+        (0..count)
+            .map(|_| {
+                let input = (0..30)
+                    .map(|_| {
+                        let (x, y, len) = (next(20) as i32, next(20) as i32, next(12) as i32);
+                        let (dx, dy) =
+                            [(1, 0), (0, 1), (1, 1), (1, -1), (-1, 0), (-1, -1)][next(6) as usize];
+                        format!("{},{} -> {},{}", x, y, x + dx * len, y + dy * len)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                super::Day05::parse(&input).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sweep() {
+        use super::Method;
+
+        let lines = super::Day05::parse(INPUT).unwrap();
+        assert_eq!(super::overlaps(&lines, false, Method::Sweep), 5);
+        assert_eq!(super::overlaps(&lines, true, Method::Sweep), 12);
+
+        for lines in generated(20) {
+            for diagonals in [false, true] {
+                assert_eq!(
+                    super::overlaps(&lines, diagonals, Method::Sweep),
+                    super::overlaps(&lines, diagonals, Method::Sparse),
+                    "{:?}",
+                    lines
+                );
+            }
+        }
+
+        let huge = super::Day05::parse(
+            "0,0 -> 2000000000,0\n1000000000,0 -> 1000000000,5\n1500000000,0 -> 1000000000,500000000\n0,0 -> 1500000000,0",
+        )
+        .unwrap();
+        assert_eq!(super::overlaps(&huge, true, Method::Sweep), 1_500_000_001);
+    }
+
+    #[test]
+    fn test_strategies() {
+        use super::Method;
+
+        for lines in generated(50) {
+            for diagonals in [false, true] {
+                let dense = super::overlaps(&lines, diagonals, Method::Dense);
+
+                assert_eq!(dense, super::overlaps(&lines, diagonals, Method::Sparse));
+                assert_eq!(dense, super::overlaps(&lines, diagonals, Method::Auto));
+            }
+        }
+
+        let spread = super::Day05::parse("-100000,0 -> 100000,0\n0,-100000 -> 0,100000").unwrap();
+        assert_eq!(super::overlaps(&spread, false, Method::Auto), 1);
+
+        let vast = super::Day05::parse(
+            "-2000000000,0 -> 0,0\n0,0 -> 2000000000,0\n0,-2000000000 -> 0,0\n0,0 -> 0,2000000000",
+        )
+        .unwrap();
+        assert!(!super::fits_dense(&vast.iter().collect::<Vec<_>>()));
+        assert_eq!(super::overlaps(&vast, false, Method::Sweep), 1);
+        assert_eq!(super::overlaps(&vast, true, Method::Dense), 1);
+    }
+
+    #[test]
+    fn test_bresenham() {
+        use super::{Method, ParseError, Raster};
+
+        const STEEP: &str = "0,0 -> 2,5\n0,5 -> 2,0\n1,0 -> 1,5";
+
+        assert_eq!(
+            super::Day05::parse(STEEP).unwrap_err(),
+            ParseError::UnsupportedSlope(2, 5)
+        );
+
+        let lines = super::parse_lines(STEEP, Raster::Bresenham).unwrap();
+        let points = lines[0].points().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+        assert_eq!(points, vec![(0, 0), (0, 1), (1, 2), (1, 3), (2, 4), (2, 5)]);
+
+        for method in [Method::Dense, Method::Sparse, Method::Sweep] {
+            assert_eq!(super::overlaps(&lines, true, method), 2);
+        }
+
+        assert!(super::Day05::parse("-2147483648,0 -> 2147483647,0").is_ok());
+        assert_eq!(
+            super::Day05::parse("-2000000000,5 -> 2000000000,6").unwrap_err(),
+            ParseError::UnsupportedSlope(4_000_000_000, 1)
+        );
+
+        let extreme =
+            super::parse_lines("-2147483648,0 -> 2147483647,1", Raster::Bresenham).unwrap();
+        let points = extreme[0]
+            .points()
+            .take(3)
+            .map(|p| (p.x, p.y))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            vec![(-2147483648, 0), (-2147483647, 0), (-2147483646, 0)]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        use super::{RenderError, VentMap};
+
+        fn render(write: impl Fn(&mut Vec<u8>) -> Result<(), RenderError>) -> String {
+            let mut out = Vec::new();
+            write(&mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        }
+
+        let lines = super::Day05::parse(INPUT).unwrap();
+        let heatmap = VentMap::new(&lines, true);
+
+        assert_eq!(
+            render(|out| heatmap.write_ascii(out, 1)),
+            "1.1....11.
+.111...2..
+..2.1.111.
+...1.2.2..
+.112313211
+...1.2....
+..1...1...
+.1.....1..
+1.......1.
+222111....
+"
+        );
+        let straight = VentMap::new(&lines, false);
+        assert_eq!(
+            render(|out| straight.write_ascii(out, 2)).lines().nth(9),
+            Some("222.......")
+        );
+        assert!(render(|out| heatmap.write_pgm(out, 2))
+            .starts_with("P2\n10 10\n3\n0 0 0 0 0 0 0 0 0 0\n0 0 0 0 0 0 0 2 0 0\n"));
+        assert_eq!(
+            render(|out| heatmap.write_csv(out, 3)),
+            "x,y,count\n4,4,3\n6,4,3\n"
+        );
+
+        let spread = super::Day05::parse("0,0 -> 99999,0\n0,0 -> 0,99999").unwrap();
+        let spread = VentMap::new(&spread, true);
+        assert!(!spread.drawable());
+        assert!(matches!(
+            spread.write_pgm(Vec::new(), 1),
+            Err(RenderError::TooLarge(100000, 100000))
+        ));
+        assert_eq!(render(|out| spread.write_csv(out, 2)), "x,y,count\n0,0,2\n");
+    }
+
+    #[test]
+    fn test_vent_map() {
+        use super::{Point, VentMap};
+
+        let lines = super::Day05::parse(INPUT).unwrap();
+        let map = VentMap::new(&lines, true);
+
+        assert_eq!(map.points_at_least(2).len(), 12);
+        assert_eq!(
+            map.points_at_least(3),
+            vec![(Point { x: 4, y: 4 }, 3), (Point { x: 6, y: 4 }, 3)]
+        );
+        assert_eq!(map.count(Point { x: 7, y: 1 }), 2);
+        assert_eq!(map.count(Point { x: 100, y: 100 }), 0);
+        assert_eq!(map.segments_through(Point { x: 4, y: 4 }), &[1, 2, 8]);
+        assert_eq!(map.most_crossed(), Some((Point { x: 4, y: 4 }, 3)));
+
+        let straight = VentMap::new(&lines, false);
+        assert_eq!(straight.segments_through(Point { x: 4, y: 4 }), &[2]);
+        assert_eq!(straight.points_at_least(2).len(), 5);
+    }
 }
@@ -75,6 +75,8 @@ impl Heightmap {
     }
 
     fn low_points(&self) -> Vec<Point> {
+        advent_of_code::span!("low points");
+
         self.inner
             .iter()
             .enumerate()
@@ -84,6 +86,8 @@ impl Heightmap {
     }
 
     fn basins(&self) -> Vec<Basin> {
+        advent_of_code::span!("basins");
+
         let low_points = self.low_points();
 
         low_points.into_iter().map(|p| self.basin(p)).collect()
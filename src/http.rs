@@ -0,0 +1,412 @@
+use std::{
+    cell::Cell,
+    error, fmt,
+    io::{self, BufRead, BufReader, Write},
+    net::TcpStream,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+const USER_AGENT: &str = "github.com/BlueGhostAlt/advent-of-code-2021";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+impl Method {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl Request {
+    pub fn get(url: String) -> Self {
+        Self {
+            method: Method::Get,
+            url,
+            headers: vec![(String::from("User-Agent"), String::from(USER_AGENT))],
+            body: None,
+        }
+    }
+
+    pub fn post(url: String, body: String) -> Self {
+        Self {
+            method: Method::Post,
+            body: Some(body),
+            ..Self::get(url)
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((String::from(name), String::from(value)));
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+pub trait Client {
+    fn send(&self, request: &Request) -> Result<Response, HttpError>;
+}
+
+/// Speaks plain HTTP/1.1 over a `TcpStream`, so it only understands `http://` URLs.
+#[derive(Debug, Default)]
+pub struct TcpClient;
+
+impl Client for TcpClient {
+    fn send(&self, request: &Request) -> Result<Response, HttpError> {
+        let rest = request
+            .url
+            .strip_prefix("http://")
+            .ok_or_else(|| HttpError::UnsupportedScheme(request.url.clone()))?;
+        let (host, path) = match rest.find('/') {
+            Some(idx) => rest.split_at(idx),
+            None => (rest, "/"),
+        };
+        if host.is_empty() {
+            return Err(HttpError::InvalidUrl(request.url.clone()));
+        }
+
+        let mut stream = if host.contains(':') {
+            TcpStream::connect(host)?
+        } else {
+            TcpStream::connect((host, 80))?
+        };
+
+        let mut head = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n",
+            request.method.as_str(),
+            path,
+            host
+        );
+        for (name, value) in &request.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        if let Some(body) = &request.body {
+            head.push_str("Content-Type: application/x-www-form-urlencoded\r\n");
+            head.push_str(&format!("Content-Length: {}\r\n", body.len()));
+        }
+        head.push_str("\r\n");
+
+        stream.write_all(head.as_bytes())?;
+        if let Some(body) = &request.body {
+            stream.write_all(body.as_bytes())?;
+        }
+
+        read_response(BufReader::new(stream))
+    }
+}
+
+fn read_response<R: BufRead>(mut reader: R) -> Result<Response, HttpError> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let status = line
+        .split_ascii_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or(HttpError::MalformedResponse)?;
+
+    let mut chunked = false;
+    let mut len = None;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(HttpError::MalformedResponse);
+        }
+
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        let (name, value) = header.split_once(':').ok_or(HttpError::MalformedResponse)?;
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        } else if name.eq_ignore_ascii_case("content-length") {
+            len = Some(value.parse().map_err(|_| HttpError::MalformedResponse)?);
+        }
+    }
+
+    let mut body = Vec::new();
+    if chunked {
+        loop {
+            line.clear();
+            reader.read_line(&mut line)?;
+            let size = line.trim_end().split(';').next().unwrap_or_default();
+            let size = usize::from_str_radix(size, 16).map_err(|_| HttpError::MalformedResponse)?;
+            if size == 0 {
+                break;
+            }
+
+            let start = body.len();
+            body.resize(start + size, 0);
+            reader.read_exact(&mut body[start..])?;
+            reader.read_line(&mut line)?;
+        }
+    } else if let Some(len) = len {
+        body.resize(len, 0);
+        reader.read_exact(&mut body)?;
+    } else {
+        reader.read_to_end(&mut body)?;
+    }
+
+    let body = String::from_utf8(body).map_err(|_| HttpError::MalformedResponse)?;
+
+    Ok(Response { status, body })
+}
+
+/// Shells out to `curl`, which gives us HTTPS without pulling in a TLS stack.
+#[derive(Debug, Default)]
+pub struct CurlClient;
+
+impl CurlClient {
+    /// The headers and body as a curl config, which is passed on stdin so that secrets like the
+    /// session cookie never show up in the process list.
+    fn config(request: &Request) -> String {
+        let mut config = String::new();
+        for (name, value) in &request.headers {
+            config.push_str(&format!(
+                "header = {}\n",
+                quote(&format!("{}: {}", name, value))
+            ));
+        }
+        if let Some(body) = &request.body {
+            config.push_str(&format!("data-raw = {}\n", quote(body)));
+        }
+
+        config
+    }
+}
+
+/// Quotes a curl config value, escaping what would otherwise end the string or the line.
+fn quote(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+
+    out
+}
+
+impl Client for CurlClient {
+    fn send(&self, request: &Request) -> Result<Response, HttpError> {
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--config", "-", "--request"])
+            .arg(request.method.as_str())
+            .args(["--write-out", "\n%{http_code}"])
+            .arg(&request.url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(Self::config(request).as_bytes())?;
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(HttpError::Curl(String::from(stderr.trim())));
+        }
+
+        let output = String::from_utf8(output.stdout).map_err(|_| HttpError::MalformedResponse)?;
+        let (body, status) = output
+            .rsplit_once('\n')
+            .ok_or(HttpError::MalformedResponse)?;
+        let status = status.parse().map_err(|_| HttpError::MalformedResponse)?;
+
+        Ok(Response {
+            status,
+            body: String::from(body),
+        })
+    }
+}
+
+/// Wraps a client so that consecutive requests are at least `interval` apart.
+#[derive(Debug)]
+pub struct Throttled<C> {
+    client: C,
+    interval: Duration,
+    last: Cell<Option<Instant>>,
+}
+
+impl<C> Throttled<C> {
+    pub fn new(client: C, interval: Duration) -> Self {
+        Self {
+            client,
+            interval,
+            last: Cell::new(None),
+        }
+    }
+}
+
+impl<C: Client> Client for Throttled<C> {
+    fn send(&self, request: &Request) -> Result<Response, HttpError> {
+        if let Some(last) = self.last.get() {
+            if let Some(wait) = self.interval.checked_sub(last.elapsed()) {
+                thread::sleep(wait);
+            }
+        }
+
+        let res = self.client.send(request);
+        self.last.set(Some(Instant::now()));
+
+        res
+    }
+}
+
+#[derive(Debug)]
+pub enum HttpError {
+    UnsupportedScheme(String),
+    InvalidUrl(String),
+    MalformedResponse,
+    Curl(String),
+    Io(io::Error),
+}
+
+impl From<io::Error> for HttpError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedScheme(url) => write!(f, "unsupported scheme in \"{}\"", url),
+            Self::InvalidUrl(url) => write!(f, "invalid url \"{}\"", url),
+            Self::MalformedResponse => write!(f, "malformed response"),
+            Self::Curl(msg) => write!(f, "curl failed: {}", msg),
+            Self::Io(io_err) => write!(f, "{}", io_err),
+        }
+    }
+}
+
+impl error::Error for HttpError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(io_err) => Some(io_err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod stub {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    /// Starts a local server answering every request with `handler(request_line, body)`
+    /// and returns its base URL together with the log of received requests.
+    pub(crate) fn serve<F>(handler: F) -> (String, Arc<Mutex<Vec<String>>>)
+    where
+        F: Fn(&str, &str) -> (u16, String) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let requests = Arc::clone(&log);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+
+                let mut head = String::new();
+                let mut len = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(n) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        len = n.trim().parse().unwrap();
+                    }
+                    if line.trim_end().is_empty() {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+
+                let mut body = vec![0; len];
+                reader.read_exact(&mut body).unwrap();
+                let body = String::from_utf8(body).unwrap();
+
+                let (status, res) = handler(head.lines().next().unwrap_or_default(), &body);
+                requests
+                    .lock()
+                    .unwrap()
+                    .push(format!("{}\r\n{}", head, body));
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    res.len(),
+                    res
+                )
+                .unwrap();
+            }
+        });
+
+        (url, log)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::{stub, Client, CurlClient, HttpError, Request};
+
+    #[test]
+    fn test() {
+        let (url, log) = stub::serve(|_, body| (200, format!("got {}", body)));
+        let request = Request::post(format!("{}/answer", url), String::from("a=\"1\"\\\n2"))
+            .header("Cookie", "session=secret");
+
+        let config = CurlClient::config(&request);
+        assert!(config.contains("header = \"Cookie: session=secret\"\n"));
+        assert!(config.contains("data-raw = \"a=\\\"1\\\"\\\\\\n2\"\n"));
+
+        let response = match CurlClient.send(&request) {
+            Err(HttpError::Io(err)) if err.kind() == io::ErrorKind::NotFound => {
+                eprintln!("skipping the curl round trip: curl is not on PATH");
+                return;
+            }
+            response => response.unwrap(),
+        };
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "got a=\"1\"\\\n2");
+        assert!(log.lock().unwrap()[0].contains("Cookie: session=secret"));
+    }
+}
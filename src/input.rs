@@ -0,0 +1,211 @@
+use std::{
+    collections::BTreeMap,
+    env, error, fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use crate::http::{Client, CurlClient, HttpError, Request, Throttled};
+
+const BASE_URL: &str = "https://adventofcode.com/2021";
+pub(crate) const REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub struct InputProvider<C> {
+    pub cache_dir: PathBuf,
+    pub base_url: String,
+    pub session_file: PathBuf,
+    client: C,
+}
+
+impl<C: Client> InputProvider<C> {
+    pub fn new(cache_dir: PathBuf, base_url: String, session_file: PathBuf, client: C) -> Self {
+        Self {
+            cache_dir,
+            base_url,
+            session_file,
+            client,
+        }
+    }
+
+    pub fn cache_path(&self, day: usize) -> PathBuf {
+        self.cache_dir.join(format!("{:02}.txt", day))
+    }
+
+    pub fn input(&self, day: usize) -> Result<String, InputError> {
+        let path = self.cache_path(day);
+
+        match fs::read_to_string(&path) {
+            Ok(input) => Ok(input),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let input = self.fetch(day)?;
+
+                fs::create_dir_all(&self.cache_dir)?;
+                let tmp = path.with_extension("txt.part");
+                fs::write(&tmp, &input)?;
+                fs::rename(&tmp, &path)?;
+
+                Ok(input)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn fetch(&self, day: usize) -> Result<String, InputError> {
+        let session = session(&self.session_file)?;
+        let url = format!("{}/day/{}/input", self.base_url.trim_end_matches('/'), day);
+
+        let res = self
+            .client
+            .send(&Request::get(url).header("Cookie", &format!("session={}", session)))?;
+
+        match res.status {
+            200 => Ok(res.body),
+            status => Err(InputError::Status(day, status)),
+        }
+    }
+}
+
+impl InputProvider<Throttled<CurlClient>> {
+    /// Caches into `input/` and reads the session token from `.session`, both overridable
+    /// through `AOC_INPUT_DIR`, `AOC_SESSION_FILE` and `AOC_BASE_URL`.
+    pub fn from_env() -> Self {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+        Self::new(
+            env::var_os("AOC_INPUT_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| root.join("input")),
+            base_url(),
+            session_file(),
+            Throttled::new(CurlClient, REQUEST_INTERVAL),
+        )
+    }
+}
+
+pub(crate) fn base_url() -> String {
+    env::var("AOC_BASE_URL").unwrap_or_else(|_| String::from(BASE_URL))
+}
+
+pub(crate) fn session_file() -> PathBuf {
+    env::var_os("AOC_SESSION_FILE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join(".session"))
+}
+
+pub(crate) fn session(path: &Path) -> Result<String, InputError> {
+    let session = fs::read_to_string(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => InputError::MissingSession(path.to_path_buf()),
+        _ => InputError::Io(err),
+    })?;
+
+    Ok(String::from(session.trim()))
+}
+
+pub fn load(day: usize) -> Result<&'static str, InputError> {
+    static PROVIDER: Mutex<Option<InputProvider<Throttled<CurlClient>>>> = Mutex::new(None);
+    static INPUTS: Mutex<BTreeMap<usize, &'static str>> = Mutex::new(BTreeMap::new());
+
+    if let Some(input) = INPUTS.lock().unwrap().get(&day) {
+        return Ok(input);
+    }
+
+    let input = PROVIDER
+        .lock()
+        .unwrap()
+        .get_or_insert_with(InputProvider::from_env)
+        .input(day)?;
+    let input = &*Box::leak(input.into_boxed_str());
+
+    Ok(INPUTS.lock().unwrap().entry(day).or_insert(input))
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    MissingSession(PathBuf),
+    Status(usize, u16),
+    Http(HttpError),
+    Io(io::Error),
+}
+
+impl From<HttpError> for InputError {
+    fn from(err: HttpError) -> Self {
+        Self::Http(err)
+    }
+}
+
+impl From<io::Error> for InputError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSession(path) => write!(f, "no session token at {}", path.display()),
+            Self::Status(day, status) => write!(
+                f,
+                "fetching the input of day {} failed with {}",
+                day, status
+            ),
+            Self::Http(http_err) => write!(f, "{}", http_err),
+            Self::Io(io_err) => write!(f, "{}", io_err),
+        }
+    }
+}
+
+impl error::Error for InputError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Http(http_err) => Some(http_err),
+            Self::Io(io_err) => Some(io_err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use crate::http::{stub, TcpClient, Throttled};
+
+    #[test]
+    fn test() {
+        let (url, requests) = stub::serve(|line, _| match line {
+            "GET /2021/day/3/input HTTP/1.1" => (200, String::from("00100\n11110\n")),
+            _ => (404, String::new()),
+        });
+
+        let dir = env::temp_dir().join(format!("aoc-input-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(".session"), "c0ffee\n").unwrap();
+
+        let provider = super::InputProvider::new(
+            dir.join("cache"),
+            format!("{}/2021", url),
+            dir.join(".session"),
+            Throttled::new(TcpClient, Duration::ZERO),
+        );
+
+        assert_eq!(provider.input(3).unwrap(), "00100\n11110\n");
+        assert_eq!(provider.input(3).unwrap(), "00100\n11110\n");
+        assert!(matches!(
+            provider.input(4),
+            Err(super::InputError::Status(4, 404))
+        ));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].contains("Cookie: session=c0ffee\r\n"));
+        assert_eq!(
+            fs::read_to_string(dir.join("cache/03.txt")).unwrap(),
+            "00100\n11110\n"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,3 +1,10 @@
+pub mod http;
+pub mod input;
+pub mod report;
+pub mod scaffold;
+pub mod span;
+pub mod submit;
+
 use std::{
     error, fmt, ops,
     sync::mpsc::channel,
@@ -18,7 +25,9 @@ where
 pub trait Day {
     fn day() -> usize;
 
-    fn input() -> &'static str;
+    fn input() -> Result<&'static str, input::InputError> {
+        input::load(Self::day())
+    }
 }
 
 #[macro_export]
@@ -31,10 +40,6 @@ macro_rules! day {
                 fn day() -> usize {
                     $day
                 }
-
-                fn input() -> &'static str {
-                    include_str!(concat!("../input/", stringify!($day), ".txt"))
-                }
             }
         }
     };
@@ -49,11 +54,63 @@ where
     pub part1: (P1, Duration),
     pub part2: (P2, Duration),
     pub total: Duration,
+    pub spans: Vec<span::Span>,
+    pub variants: Vec<VariantBench>,
+}
+
+impl<P1, P2> DayBench<P1, P2>
+where
+    P1: fmt::Debug + PartialEq + Send + 'static,
+    P2: fmt::Debug + PartialEq + Send + 'static,
+{
+    pub fn describe(&self) -> DayBench<String, String> {
+        DayBench {
+            part1: (format!("{:?}", self.part1.0), self.part1.1),
+            part2: (format!("{:?}", self.part2.0), self.part2.1),
+            total: self.total,
+            spans: self.spans.clone(),
+            variants: self.variants.clone(),
+        }
+    }
+}
+
+/// An alternative implementation of both parts, run on the same input and checked against the
+/// same answers as the main one.
+#[derive(Debug)]
+pub struct Variant<T: ?Sized, P1, P2> {
+    pub name: &'static str,
+    pub part1: fn(&T) -> P1,
+    pub part2: fn(&T) -> P2,
+}
+
+pub type Variants<'a, S> = Vec<
+    Variant<
+        <<S as Solution<'a>>::Input as ops::Deref>::Target,
+        <S as Solution<'a>>::P1,
+        <S as Solution<'a>>::P2,
+    >,
+>;
+
+#[derive(Debug, Clone)]
+pub struct VariantBench {
+    pub name: &'static str,
+    pub part1: Duration,
+    pub part2: Duration,
 }
 
 #[derive(Debug)]
 pub enum RunError {
     Unexpected(Box<dyn fmt::Debug + Send>, Box<dyn fmt::Debug + Send>),
+    Variant(&'static str, String, String),
+    Parse(String),
+    Panicked,
+    Input(input::InputError),
+}
+
+impl From<input::InputError> for RunError {
+    fn from(err: input::InputError) -> Self {
+        Self::Input(err)
+    }
 }
 
 impl fmt::Display for RunError {
@@ -62,13 +119,24 @@ impl fmt::Display for RunError {
             Self::Unexpected(ex, ac) => {
                 write!(f, "expected answer {:?} but instead got {:?}", ex, ac)
             }
+            Self::Variant(name, ex, ac) => write!(
+                f,
+                "variant {} expected answer {} but instead got {}",
+                name, ex, ac
+            ),
+            Self::Parse(err) => write!(f, "failed to parse the input: {}", err),
+            Self::Panicked => write!(f, "the solution panicked"),
+            Self::Input(input_err) => write!(f, "{}", input_err),
         }
     }
 }
 
 impl error::Error for RunError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        None
+        match self {
+            Self::Input(input_err) => Some(input_err),
+            _ => None,
+        }
     }
 }
 
@@ -76,8 +144,8 @@ pub trait Solution<'a>: Day {
     type Input: ops::Deref;
     type ParseError: error::Error;
 
-    type P1: fmt::Debug + PartialEq + Send + 'static;
-    type P2: fmt::Debug + PartialEq + Send + 'static;
+    type P1: fmt::Debug + PartialEq + submit::Answer + Send + 'static;
+    type P2: fmt::Debug + PartialEq + submit::Answer + Send + 'static;
 
     fn parse(input: &'a str) -> Result<Self::Input, Self::ParseError>;
 
@@ -85,6 +153,10 @@ pub trait Solution<'a>: Day {
 
     fn part2(input: &<Self::Input as ops::Deref>::Target) -> Self::P2;
 
+    fn variants() -> Variants<'a, Self> {
+        Vec::new()
+    }
+
     fn solve(input: &'a str) -> Result<(Self::P1, Self::P2), Self::ParseError> {
         let input = Self::parse(input)?;
 
@@ -102,31 +174,69 @@ pub trait Solution<'a>: Day {
         let (tx, rx) = channel();
 
         thread::spawn(move || {
-            let input = Self::parse(input).unwrap();
+            let ((input, parse_spans), parse_dur) = bench(|| span::collect(|| Self::parse(input)));
+            let input = match input {
+                Ok(input) => input,
+                Err(err) => {
+                    tx.send(Err(RunError::Parse(err.to_string()))).unwrap();
+                    return;
+                }
+            };
 
-            let (((p1, p1_dur), (p2, p2_dur)), total_dur) = bench(|| {
-                let (p1, p1_dur) = bench(|| Self::part1(&input));
-                let (p2, p2_dur) = bench(|| Self::part2(&input));
+            let ((((p1, p1_spans), p1_dur), ((p2, p2_spans), p2_dur)), total_dur) = bench(|| {
+                let p1 = bench(|| span::collect(|| Self::part1(&input)));
+                let p2 = bench(|| span::collect(|| Self::part2(&input)));
 
-                ((p1, p1_dur), (p2, p2_dur))
+                (p1, p2)
             });
 
+            let variants = Self::variants()
+                .into_iter()
+                .map(|v| {
+                    let (v1, v1_dur) = bench(|| (v.part1)(&input));
+                    let (v2, v2_dur) = bench(|| (v.part2)(&input));
+
+                    let mismatch = if v1 != ans1 {
+                        Some((format!("{:?}", ans1), format!("{:?}", v1)))
+                    } else if v2 != ans2 {
+                        Some((format!("{:?}", ans2), format!("{:?}", v2)))
+                    } else {
+                        None
+                    };
+
+                    match mismatch {
+                        Some((ex, ac)) => Err(RunError::Variant(v.name, ex, ac)),
+                        None => Ok(VariantBench {
+                            name: v.name,
+                            part1: v1_dur,
+                            part2: v2_dur,
+                        }),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>();
+
             let res = if p1 != ans1 {
                 Err(RunError::Unexpected(Box::new(ans1), Box::new(p1)))
             } else if p2 != ans2 {
                 Err(RunError::Unexpected(Box::new(ans2), Box::new(p2)))
             } else {
-                Ok(DayBench {
+                variants.map(|variants| DayBench {
                     part1: (p1, p1_dur),
                     part2: (p2, p2_dur),
                     total: total_dur,
+                    spans: vec![
+                        span::Span::new("parse", parse_dur, parse_spans),
+                        span::Span::new("part 1", p1_dur, p1_spans),
+                        span::Span::new("part 2", p2_dur, p2_spans),
+                    ],
+                    variants,
                 })
             };
 
             tx.send(res).unwrap();
         });
 
-        rx.recv().unwrap()
+        rx.recv().unwrap_or(Err(RunError::Panicked))
     }
 }
 
@@ -134,21 +244,45 @@ pub trait Solution<'a>: Day {
 macro_rules! days {
     ($($day: expr => ($ans1: expr, $ans2: expr)),+) => {
         paste::paste! {
-            let now = std::time::Instant::now();
+            fn bench() -> (Vec<advent_of_code::report::DayReport>, std::time::Duration) {
+                let now = std::time::Instant::now();
 
-            $(
-                let [<day_ $day>] = [<day $day>]::[<Day $day>]::run([<day $day>]::[<Day $day>]::input(), $ans1, $ans2).unwrap();
-            )+
+                let days = vec![
+                    $(
+                        advent_of_code::report::DayReport {
+                            day: $day,
+                            bench: [<day $day>]::[<Day $day>]::input()
+                                .map_err(advent_of_code::RunError::from)
+                                .and_then(|input| [<day $day>]::[<Day $day>]::run(input, $ans1, $ans2))
+                                .map(|bench| bench.describe()),
+                        },
+                    )+
+                ];
+
+                (days, now.elapsed())
+            }
 
-            let total = now.elapsed();
+            type Answers = (Option<String>, Option<String>);
 
-            $(
-                println!(
-                    "Day {}({:?}):\n    Part 1({:?}): {:?}\n    Part 2({:?}): {:?}",
-                    $day, [<day_ $day>].total, [<day_ $day>].part1.1, [<day_ $day>].part1.0, [<day_ $day>].part2.1, [<day_ $day>].part2.0
-                );
-            )+
-            println!("\nTotal: {:?}", total);
+            fn solve(day: usize) -> Option<Result<Answers, Box<dyn std::error::Error>>> {
+                use advent_of_code::submit::Answer;
+
+                $(
+                    if day == $day {
+                        let answers = [<day $day>]::[<Day $day>]::input()
+                            .map_err(|err| Box::new(err) as Box<dyn std::error::Error>)
+                            .and_then(|input| {
+                                [<day $day>]::[<Day $day>]::solve(input)
+                                    .map_err(|err| Box::new(err) as Box<dyn std::error::Error>)
+                            })
+                            .map(|(p1, p2)| (p1.answer(), p2.answer()));
+
+                        return Some(answers);
+                    }
+                )+
+
+                None
+            }
         }
     };
 }
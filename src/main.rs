@@ -6,7 +6,15 @@
     option_result_contains
 )]
 
-use advent_of_code::{Day, Solution};
+use std::{
+    env, fmt, fs,
+    io::{self, BufRead, Write},
+    path::Path,
+    process, thread,
+    time::Duration,
+};
+
+use advent_of_code::{report, scaffold, submit::Submitter, Day, Solution};
 
 mod day01;
 mod day02;
@@ -19,17 +27,435 @@ mod day08;
 mod day09;
 mod day10;
 
+advent_of_code::days!(
+    01 => (1_233, 1_275),
+    02 => (Ok(1_882_980), Ok(1_971_232_560)),
+    03 => (Some(1_071_734.into()), Some(6_124_992.into())),
+    04 => (Some(49_860), Some(24_628)),
+    05 => (6_856, 20_666),
+    06 => (355_386, 1_613_415_325_809),
+    07 => (Some(342_730), Some(92_335_207)),
+    08 => (470, Some(989_396)),
+    09 => (566, 891_684),
+    10 => (339_411, 2_289_754_624)
+);
+
 fn main() {
-    advent_of_code::days!(
-        01 => (1_233, 1_275),
-        02 => (1_882_980, 1_971_232_560),
-        03 => (Some(1_071_734), Some(6_124_992)),
-        04 => (Some(49_860), Some(24_628)),
-        05 => (6_856, 20_666),
-        06 => (355_386, 1_613_415_325_809),
-        07 => (Some(342_730), Some(92_335_207)),
-        08 => (470, Some(989_396)),
-        09 => (566, 891_684),
-        10 => (339_411, 2_289_754_624)
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        [] => run(false),
+        ["-v" | "--verbose"] => run(true),
+        ["report"] => write_report(&Path::new(env!("CARGO_MANIFEST_DIR")).join("reports")),
+        ["report", dir] => write_report(Path::new(dir)),
+        ["new", day] => new(day),
+        ["submit", day, part] => submit(day, part),
+        ["day01", "stats", ref args @ ..] => sonar_stats(args),
+        ["day01", "monitor"] => sonar_monitor(),
+        ["day02", "trace", ref args @ ..] => course_trace(args),
+        ["day03", "rating", ref args @ ..] => diagnostic_rating(args),
+        ["day04", "ranking", ref args @ ..] => bingo_ranking(args),
+        ["day04", "replay", ref args @ ..] => bingo_replay(args),
+        ["day05", "overlaps", ref args @ ..] => vent_overlaps(args),
+        ["day05", "render", ref args @ ..] => vent_render(args),
+        ["day05", "query", ref args @ ..] => vent_query(args),
+        _ => usage(),
+    }
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: advent_of_code [-v | --verbose]
+       advent_of_code report [<dir>]
+       advent_of_code new <day>
+       advent_of_code submit <day> <part>
+       advent_of_code day01 stats [--csv] [--window <n>] [--bucket <n>] [<file>]
+       advent_of_code day01 monitor < <readings>
+       advent_of_code day02 trace [--steering <model>] [--csv | --ascii | --svg] [<script>]
+       advent_of_code day03 rating [--minority] [--ties <0 | 1>] [<file>]
+       advent_of_code day04 ranking [--rules <rule,...>] [<file>]
+       advent_of_code day04 replay [--ansi] [--delay <ms>] [<file>]
+       advent_of_code day05 overlaps [--dense | --sparse | --sweep] [--no-diagonals]
+                                  [--bresenham] [<file>]
+       advent_of_code day05 render [--ascii | --pgm | --csv] [--no-diagonals]
+                                [--threshold <n>] [--bresenham] [<file>]
+       advent_of_code day05 query [--at <x,y> | --at-least <k>] [--no-diagonals]
+                               [--bresenham] [<file>]"
     );
+    process::exit(2)
+}
+
+fn fail(err: impl fmt::Display) -> ! {
+    eprintln!("error: {}", err);
+    process::exit(1)
+}
+
+fn run(verbose: bool) {
+    let (days, total) = bench();
+
+    report::print(&days, total, verbose);
+}
+
+fn write_report(dir: &Path) {
+    let (days, total) = bench();
+
+    let path = report::write(dir, &days, total).unwrap_or_else(|err| fail(err));
+    println!("{}", path.display());
+}
+
+fn new(day: &str) {
+    let day = day.parse().unwrap_or_else(|_| usage());
+
+    scaffold::generate(Path::new(env!("CARGO_MANIFEST_DIR")), day).unwrap_or_else(|err| fail(err));
+}
+
+fn submit(day: &str, part: &str) {
+    let day = day.parse().unwrap_or_else(|_| usage());
+    let part = match part {
+        "1" => 1,
+        "2" => 2,
+        _ => usage(),
+    };
+
+    let (p1, p2) = solve(day)
+        .unwrap_or_else(|| fail(format!("day {} is not registered", day)))
+        .unwrap_or_else(|err| fail(err));
+    let answer = if part == 1 { p1 } else { p2 }
+        .unwrap_or_else(|| fail(format!("day {} part {} has no answer", day, part)));
+
+    let verdict = Submitter::from_env()
+        .submit(day, part, &answer)
+        .unwrap_or_else(|err| fail(err));
+    println!("{}: {}", answer, verdict);
+}
+
+fn read_input<D: Day>(file: Option<&str>) -> String {
+    match file {
+        Some(path) => fs::read_to_string(path).unwrap_or_else(|err| fail(err)),
+        None => String::from(D::input().unwrap_or_else(|err| fail(err))),
+    }
+}
+
+fn sonar_stats(args: &[&str]) {
+    let (mut csv, mut window, mut bucket, mut file) = (false, 3, 1, None);
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--csv" => csv = true,
+            "--window" => {
+                window = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "--bucket" => {
+                bucket = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            _ if file.is_none() => file = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let input = read_input::<day01::Day01>(file);
+    let depths = day01::Day01::parse(&input).unwrap_or_else(|err| fail(err));
+    let stats =
+        day01::stats(&depths, window, bucket).unwrap_or_else(|| fail("no depths to analyse"));
+
+    if csv {
+        print!("{}", stats.to_csv(&depths));
+    } else {
+        print!("{}", stats.to_text());
+    }
+}
+
+fn sonar_monitor() {
+    let mut monitor = day01::DepthMonitor::new();
+
+    for line in io::stdin().lock().lines() {
+        let line = line.unwrap_or_else(|err| fail(err));
+        let depth = line
+            .trim()
+            .parse()
+            .unwrap_or_else(|err| fail(format!("reading {}: {}", monitor.seen() + 1, err)));
+
+        monitor.push(depth);
+        println!("{} {}", monitor.increases(), monitor.window_increases());
+    }
+}
+
+fn course_trace(args: &[&str]) {
+    let (mut steering, mut format, mut file) = (None, "--csv", None);
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--steering" => {
+                let name = args.next().unwrap_or_else(|| usage());
+                steering = Some(name.parse().unwrap_or_else(|err| fail(err)));
+            }
+            "--csv" | "--ascii" | "--svg" => format = arg,
+            _ if file.is_none() => file = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let input = read_input::<day02::Day02>(file);
+    let script = input
+        .parse::<day02::Script>()
+        .unwrap_or_else(|err| fail(err));
+
+    match steering
+        .or(script.steering)
+        .unwrap_or(day02::Steering::Direct)
+    {
+        day02::Steering::Direct => export_course(&script.commands, day02::Direct, format),
+        day02::Steering::Aim => export_course(&script.commands, day02::Aim, format),
+        day02::Steering::Lateral => export_course(&script.commands, day02::Lateral, format),
+    }
+}
+
+fn export_course<M: day02::SteeringModel>(commands: &[day02::Command], model: M, format: &str) {
+    let states = day02::trace(commands, model)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|err| fail(err));
+
+    match format {
+        "--ascii" => print!("{}", day02::depth_profile(&states, 80, 20)),
+        "--svg" => print!("{}", day02::depth_profile_svg(&states, 800, 400)),
+        _ => print!("{}", day02::path_csv(&states)),
+    }
+}
+
+fn diagnostic_rating(args: &[&str]) {
+    let mut criteria = day03::BitCriteria::OXYGEN;
+    let mut file = None;
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--minority" => criteria.selection = day03::Selection::Minority,
+            "--ties" => {
+                criteria.tie = match args.next() {
+                    Some(&"0") => day03::TieBreak::Zero,
+                    Some(&"1") => day03::TieBreak::One,
+                    _ => usage(),
+                }
+            }
+            _ if file.is_none() => file = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let input = read_input::<day03::Day03>(file);
+    let diagnostics = day03::Day03::parse(&input).unwrap_or_else(|err| fail(err));
+    let survivor = diagnostics
+        .rating(criteria)
+        .unwrap_or_else(|| fail("no single line survives the criteria"));
+
+    for step in &survivor.trace {
+        println!(
+            "bit {}: {} ones, {} zeros, kept {}, {} left",
+            step.bit_pos, step.ones, step.zeros, step.kept as u8, step.remaining
+        );
+    }
+    println!("{} ({})", survivor.line, survivor.line.to_decimal());
+}
+
+fn bingo_ranking(args: &[&str]) {
+    let (mut rules, mut file) = (day04::WinRule::CLASSIC.to_vec(), None);
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--rules" => {
+                rules = args
+                    .next()
+                    .unwrap_or_else(|| usage())
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .unwrap_or_else(|err| fail(err))
+            }
+            _ if file.is_none() => file = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let input = read_input::<day04::Day04>(file);
+    let bingo = day04::Day04::parse(&input).unwrap_or_else(|err| fail(err));
+    let ranking = day04::ranking(&bingo, &rules);
+
+    for place in &ranking.places {
+        let tie = if ranking.is_tied(place) { "=" } else { "" };
+        println!(
+            "{}{}. board {} wins on draw {} with {} scoring {}",
+            tie, place.rank, place.win.board, place.win.draw, place.win.line, place.win.score
+        );
+    }
+    for board in &ranking.never_won {
+        println!("-. board {} never wins", board);
+    }
+}
+
+fn bingo_replay(args: &[&str]) {
+    let (mut style, mut delay, mut file) = (day04::Style::Brackets, None, None);
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--ansi" => style = day04::Style::Ansi,
+            "--delay" => {
+                delay = args
+                    .next()
+                    .and_then(|ms| ms.parse().ok())
+                    .map(Duration::from_millis)
+                    .or_else(|| usage())
+            }
+            _ if file.is_none() => file = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let input = read_input::<day04::Day04>(file);
+    let bingo = day04::Day04::parse(&input).unwrap_or_else(|err| fail(err));
+
+    for frame in day04::replay(&bingo, style) {
+        match delay {
+            Some(delay) => {
+                print!("\x1b[2J\x1b[H{}", frame);
+                io::stdout().flush().unwrap_or_else(|err| fail(err));
+                thread::sleep(delay);
+            }
+            None => println!("{}", frame),
+        }
+    }
+}
+
+fn vent_overlaps(args: &[&str]) {
+    let (mut method, mut diagonals, mut file) = (day05::Method::Auto, true, None);
+    let mut raster = day05::Raster::Octilinear;
+
+    for &arg in args {
+        match arg {
+            "--bresenham" => raster = day05::Raster::Bresenham,
+            "--dense" => method = day05::Method::Dense,
+            "--sparse" => method = day05::Method::Sparse,
+            "--sweep" => method = day05::Method::Sweep,
+            "--no-diagonals" => diagonals = false,
+            _ if file.is_none() => file = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let input = read_input::<day05::Day05>(file);
+    let lines = day05::parse_lines(&input, raster).unwrap_or_else(|err| fail(err));
+
+    println!("{}", day05::overlaps(&lines, diagonals, method));
+}
+
+fn vent_render(args: &[&str]) {
+    let (mut format, mut diagonals, mut threshold, mut file) = (None, true, 1, None);
+    let mut raster = day05::Raster::Octilinear;
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--ascii" | "--pgm" | "--csv" => format = Some(arg),
+            "--no-diagonals" => diagonals = false,
+            "--threshold" => {
+                threshold = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "--bresenham" => raster = day05::Raster::Bresenham,
+            _ if file.is_none() => file = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let input = read_input::<day05::Day05>(file);
+    let lines = day05::parse_lines(&input, raster).unwrap_or_else(|err| fail(err));
+    let heatmap = day05::VentMap::new(&lines, diagonals);
+
+    // Small maps fit in a terminal and bigger ones are more useful as an image, while maps too
+    // large to draw can only be listed.
+    let format = format.unwrap_or(if !heatmap.drawable() {
+        "--csv"
+    } else if heatmap.width() <= 100 && heatmap.height() <= 50 {
+        "--ascii"
+    } else {
+        "--pgm"
+    });
+
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    match format {
+        "--ascii" => heatmap.write_ascii(&mut out, threshold),
+        "--csv" => heatmap.write_csv(&mut out, threshold),
+        _ => heatmap.write_pgm(&mut out, threshold),
+    }
+    .and_then(|()| out.flush().map_err(day05::RenderError::from))
+    .unwrap_or_else(|err| fail(err));
+}
+
+fn vent_query(args: &[&str]) {
+    let (mut at, mut at_least, mut diagonals, mut file) = (None, None, true, None);
+    let mut raster = day05::Raster::Octilinear;
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--at" => {
+                at = args
+                    .next()
+                    .and_then(|p| p.split_once(','))
+                    .and_then(|(x, y)| {
+                        Some(day05::Point {
+                            x: x.parse().ok()?,
+                            y: y.parse().ok()?,
+                        })
+                    })
+                    .or_else(|| usage())
+            }
+            "--at-least" => at_least = args.next().and_then(|k| k.parse().ok()).or_else(|| usage()),
+            "--no-diagonals" => diagonals = false,
+            "--bresenham" => raster = day05::Raster::Bresenham,
+            _ if file.is_none() => file = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let input = read_input::<day05::Day05>(file);
+    let lines = day05::parse_lines(&input, raster).unwrap_or_else(|err| fail(err));
+    let map = day05::VentMap::new(&lines, diagonals);
+
+    match (at, at_least) {
+        (Some(point), _) => {
+            let segments = map
+                .segments_through(point)
+                .iter()
+                .map(|idx| (idx + 1).to_string())
+                .collect::<Vec<_>>();
+            println!(
+                "{},{}: {} (lines {})",
+                point.x,
+                point.y,
+                segments.len(),
+                segments.join(", ")
+            );
+        }
+        (None, Some(k)) => {
+            for (p, count) in map.points_at_least(k) {
+                println!("{},{}: {}", p.x, p.y, count);
+            }
+        }
+        (None, None) => match map.most_crossed() {
+            Some((p, count)) => println!("{},{}: {}", p.x, p.y, count),
+            None => fail("no vents to query"),
+        },
+    }
 }
@@ -0,0 +1,382 @@
+use std::{
+    collections::BTreeMap,
+    error, fmt,
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{DayBench, RunError};
+
+const HISTORY: &str = "history.csv";
+
+const BAR_WIDTH: f64 = 480.0;
+const BAR_HEIGHT: usize = 14;
+const LABEL_WIDTH: usize = 70;
+
+#[derive(Debug)]
+pub struct DayReport {
+    pub day: usize,
+    pub bench: Result<DayBench<String, String>, RunError>,
+}
+
+pub fn print(days: &[DayReport], total: Duration, verbose: bool) {
+    for DayReport { day, bench } in days {
+        match bench {
+            Ok(bench) => {
+                println!(
+                    "Day {}({:?}):\n    Part 1({:?}): {}\n    Part 2({:?}): {}",
+                    day, bench.total, bench.part1.1, bench.part1.0, bench.part2.1, bench.part2.0
+                );
+                for variant in &bench.variants {
+                    println!(
+                        "    Variant {}: Part 1({:?}), Part 2({:?})",
+                        variant.name, variant.part1, variant.part2
+                    );
+                }
+
+                if verbose {
+                    println!("    Spans:");
+                    bench
+                        .spans
+                        .iter()
+                        .flat_map(|span| span.lines(2))
+                        .for_each(|l| println!("{}", l));
+                }
+            }
+            Err(err) => println!("Day {}: {}", day, err),
+        }
+    }
+    println!("\nTotal: {:?}", total);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub timestamp: u64,
+    pub day: usize,
+    pub part1: Duration,
+    pub part2: Duration,
+    pub total: Duration,
+}
+
+pub fn read_history(path: &Path) -> Result<Vec<HistoryEntry>, ReportError> {
+    let history = match fs::read_to_string(path) {
+        Ok(history) => history,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    history
+        .lines()
+        .map(|l| {
+            let corrupt = || ReportError::CorruptHistory(String::from(l));
+            let fields = l
+                .split(',')
+                .map(|f| f.parse::<u64>().map_err(|_| corrupt()))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            match fields[..] {
+                [timestamp, day, part1, part2, total] => Ok(HistoryEntry {
+                    timestamp,
+                    day: day as usize,
+                    part1: Duration::from_nanos(part1),
+                    part2: Duration::from_nanos(part2),
+                    total: Duration::from_nanos(total),
+                }),
+                _ => Err(corrupt()),
+            }
+        })
+        .collect()
+}
+
+/// Writes `report-<timestamp>.html` into `dir` and records the timings of the passing days
+/// in `dir/history.csv`, which later reports compare against.
+pub fn write(dir: &Path, days: &[DayReport], total: Duration) -> Result<PathBuf, ReportError> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+
+    let history = read_history(&dir.join(HISTORY))?;
+    let links = history
+        .iter()
+        .map(|e| e.timestamp)
+        .filter(|ts| dir.join(report_name(*ts)).exists())
+        .collect::<Vec<_>>();
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(report_name(timestamp));
+    fs::write(&path, html(days, total, &history, &links))?;
+
+    let mut csv = fs::read_to_string(dir.join(HISTORY)).unwrap_or_default();
+    for DayReport { day, bench } in days {
+        if let Ok(bench) = bench {
+            writeln!(
+                csv,
+                "{},{},{},{},{}",
+                timestamp,
+                day,
+                bench.part1.1.as_nanos(),
+                bench.part2.1.as_nanos(),
+                bench.total.as_nanos()
+            )
+            .unwrap();
+        }
+    }
+    fs::write(dir.join(HISTORY), csv)?;
+
+    Ok(path)
+}
+
+fn report_name(timestamp: u64) -> String {
+    format!("report-{}.html", timestamp)
+}
+
+pub fn html(
+    days: &[DayReport],
+    total: Duration,
+    history: &[HistoryEntry],
+    links: &[u64],
+) -> String {
+    let mut out = String::new();
+
+    out.push_str(concat!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n",
+        "<title>Advent of Code 2021</title>\n<style>\n",
+        "body { font-family: sans-serif; margin: 2em; }\n",
+        "table { border-collapse: collapse; margin-bottom: 2em; }\n",
+        "th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }\n",
+        ".badge { border-radius: 0.3em; color: white; padding: 0.1em 0.5em; }\n",
+        ".pass { background: #2a9d4a; }\n.fail { background: #c8323c; }\n",
+        "</style>\n</head>\n<body>\n<h1>Advent of Code 2021</h1>\n",
+    ));
+    writeln!(out, "<p>Total: {}</p>", escape(&format!("{:?}", total))).unwrap();
+
+    out.push_str("<h2>Results</h2>\n<table>\n");
+    out.push_str("<tr><th>Day</th><th>Status</th><th>Part 1</th><th>Time</th><th>Part 2</th><th>Time</th><th>Total</th></tr>\n");
+    for DayReport { day, bench } in days {
+        match bench {
+            Ok(bench) => writeln!(
+                out,
+                "<tr><td>{}</td><td><span class=\"badge pass\">pass</span></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                day,
+                escape(&bench.part1.0),
+                escape(&format!("{:?}", bench.part1.1)),
+                escape(&bench.part2.0),
+                escape(&format!("{:?}", bench.part2.1)),
+                escape(&format!("{:?}", bench.total)),
+            ),
+            Err(err) => writeln!(
+                out,
+                "<tr><td>{}</td><td><span class=\"badge fail\">fail</span></td><td colspan=\"5\">{}</td></tr>",
+                day,
+                escape(&err.to_string()),
+            ),
+        }
+        .unwrap();
+    }
+    out.push_str("</table>\n");
+
+    out.push_str("<h2>Timings</h2>\n");
+    out.push_str(&chart(days));
+
+    let previous = history.iter().fold(BTreeMap::new(), |mut acc, e| {
+        acc.insert(e.day, e);
+        acc
+    });
+    if !previous.is_empty() {
+        out.push_str("<h2>Compared to the previous run</h2>\n<table>\n");
+        out.push_str("<tr><th>Day</th><th>Previous</th><th>Now</th><th>Change</th></tr>\n");
+        for DayReport { day, bench } in days {
+            if let (Some(prev), Ok(bench)) = (previous.get(day), bench) {
+                let change = (bench.total.as_secs_f64() / prev.total.as_secs_f64() - 1.0) * 100.0;
+                writeln!(
+                    out,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:+.1}%</td></tr>",
+                    day,
+                    escape(&format!("{:?}", prev.total)),
+                    escape(&format!("{:?}", bench.total)),
+                    change
+                )
+                .unwrap();
+            }
+        }
+        out.push_str("</table>\n");
+    }
+
+    if !links.is_empty() {
+        out.push_str("<h2>Earlier reports</h2>\n<ul>\n");
+        let mut links = links.to_vec();
+        links.sort_unstable();
+        links.dedup();
+        for ts in links.iter().rev() {
+            writeln!(out, "<li><a href=\"{0}\">{0}</a></li>", report_name(*ts)).unwrap();
+        }
+        out.push_str("</ul>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+
+    out
+}
+
+fn chart(days: &[DayReport]) -> String {
+    let benches = days
+        .iter()
+        .filter_map(|DayReport { day, bench }| Some((day, bench.as_ref().ok()?)))
+        .collect::<Vec<_>>();
+    let max = benches
+        .iter()
+        .flat_map(|(_, b)| [b.part1.1, b.part2.1])
+        .max()
+        .unwrap_or_default()
+        .as_secs_f64();
+
+    let row = 2 * BAR_HEIGHT + 8;
+    let mut out = String::new();
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-size=\"11\">",
+        LABEL_WIDTH as f64 + BAR_WIDTH + 120.0,
+        row * benches.len()
+    )
+    .unwrap();
+
+    for (idx, (day, bench)) in benches.iter().enumerate() {
+        let y = idx * row;
+        writeln!(
+            out,
+            "<text x=\"0\" y=\"{}\">Day {}</text>",
+            y + BAR_HEIGHT + 4,
+            day
+        )
+        .unwrap();
+
+        for (part, (dur, colour)) in [(bench.part1.1, "#4a7bd0"), (bench.part2.1, "#e0a030")]
+            .iter()
+            .enumerate()
+        {
+            let width = if max > 0.0 {
+                (dur.as_secs_f64() / max * BAR_WIDTH).max(1.0)
+            } else {
+                1.0
+            };
+            let y = y + part * BAR_HEIGHT;
+
+            writeln!(
+                out,
+                "<rect x=\"{}\" y=\"{}\" width=\"{:.1}\" height=\"{}\" fill=\"{}\"><title>Part {}</title></rect>",
+                LABEL_WIDTH,
+                y,
+                width,
+                BAR_HEIGHT - 2,
+                colour,
+                part + 1
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "<text x=\"{:.1}\" y=\"{}\">{}</text>",
+                LABEL_WIDTH as f64 + width + 4.0,
+                y + BAR_HEIGHT - 4,
+                escape(&format!("{:?}", dur))
+            )
+            .unwrap();
+        }
+    }
+    out.push_str("</svg>\n");
+
+    out
+}
+
+fn escape(s: &str) -> String {
+    s.chars()
+        .fold(String::with_capacity(s.len()), |mut acc, c| {
+            match c {
+                '&' => acc.push_str("&amp;"),
+                '<' => acc.push_str("&lt;"),
+                '>' => acc.push_str("&gt;"),
+                '"' => acc.push_str("&quot;"),
+                _ => acc.push(c),
+            }
+            acc
+        })
+}
+
+#[derive(Debug)]
+pub enum ReportError {
+    CorruptHistory(String),
+    Io(io::Error),
+}
+
+impl From<io::Error> for ReportError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CorruptHistory(line) => write!(f, "corrupt history entry \"{}\"", line),
+            Self::Io(io_err) => write!(f, "{}", io_err),
+        }
+    }
+}
+
+impl error::Error for ReportError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(io_err) => Some(io_err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{DayReport, HistoryEntry};
+    use crate::{DayBench, RunError};
+
+    #[test]
+    fn test() {
+        let days = [
+            DayReport {
+                day: 1,
+                bench: Ok(DayBench {
+                    part1: (String::from("7"), Duration::from_micros(20)),
+                    part2: (String::from("5"), Duration::from_micros(40)),
+                    total: Duration::from_micros(60),
+                    spans: Vec::new(),
+                    variants: Vec::new(),
+                }),
+            },
+            DayReport {
+                day: 2,
+                bench: Err(RunError::Unexpected(Box::new(150), Box::new(151))),
+            },
+            DayReport {
+                day: 3,
+                bench: Err(RunError::Parse(String::from("invalid digit"))),
+            },
+        ];
+        let history = [HistoryEntry {
+            timestamp: 1,
+            day: 1,
+            part1: Duration::from_micros(10),
+            part2: Duration::from_micros(20),
+            total: Duration::from_micros(30),
+        }];
+
+        let html = super::html(&days, Duration::from_micros(70), &history, &[1]);
+
+        assert!(html.contains("<span class=\"badge pass\">pass</span></td><td>7</td>"));
+        assert!(html.contains("<span class=\"badge fail\">fail</span></td><td colspan=\"5\">expected answer 150 but instead got 151</td>"));
+        assert!(html.contains("<td colspan=\"5\">failed to parse the input: invalid digit</td>"));
+        assert_eq!(html.matches("<rect ").count(), 2);
+        assert!(html.contains("<td>+100.0%</td>"));
+        assert!(html.contains("<a href=\"report-1.html\">"));
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    error, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+const TEMPLATE: &str = "advent_of_code::day!({day});
+
+impl<'a> advent_of_code::Solution<'a> for Day{day} {
+    type Input = Vec<&'a str>;
+    type ParseError = ParseError;
+
+    type P1 = Option<u32>;
+    type P2 = Option<u32>;
+
+    fn parse(input: &'a str) -> Result<Self::Input, Self::ParseError> {
+        Ok(input.lines().collect())
+    }
+
+    fn part1(_input: &[&'a str]) -> Self::P1 {
+        None
+    }
+
+    fn part2(_input: &[&'a str]) -> Self::P2 {
+        None
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {}
+
+use std::{error, fmt};
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use advent_of_code::Solution;
+
+    const INPUT: &str = \"\";
+
+    #[test]
+    fn test() {
+        assert_eq!(super::Day{day}::solve(INPUT), Ok((None, None)));
+    }
+}
+";
+
+const DAYS_MACRO: &str = "advent_of_code::days!(";
+
+pub fn generate(root: &Path, day: usize) -> Result<(), ScaffoldError> {
+    if !(1..=25).contains(&day) {
+        return Err(ScaffoldError::DayOutOfRange(day));
+    }
+
+    let module = root.join("src").join(format!("day{:02}.rs", day));
+    if module.exists() {
+        return Err(ScaffoldError::AlreadyExists(module));
+    }
+
+    let main = root.join("src").join("main.rs");
+    let registered = register(&fs::read_to_string(&main)?, day)?;
+
+    fs::write(&module, TEMPLATE.replace("{day}", &format!("{:02}", day)))?;
+    fs::write(&main, registered)?;
+
+    Ok(())
+}
+
+pub fn register(main: &str, day: usize) -> Result<String, ScaffoldError> {
+    let module = format!("mod day{:02};", day);
+    let entry = format!("{:02} => (None, None)", day);
+
+    let mut lines = main.lines().map(String::from).collect::<Vec<_>>();
+
+    let mods = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, l)| Some((idx, parse_day(l.strip_prefix("mod day")?)?)))
+        .collect::<Vec<_>>();
+    if mods.iter().any(|&(_, d)| d == day) {
+        return Err(ScaffoldError::AlreadyRegistered(day));
+    }
+    let mod_idx = mods
+        .iter()
+        .find(|&&(_, d)| d > day)
+        .map(|&(idx, _)| idx)
+        .or_else(|| mods.last().map(|&(idx, _)| idx + 1))
+        .ok_or(ScaffoldError::MissingModules)?;
+    lines.insert(mod_idx, module);
+
+    let start = lines
+        .iter()
+        .position(|l| l.trim() == DAYS_MACRO)
+        .ok_or(ScaffoldError::MissingDaysList)?
+        + 1;
+    let end = lines[start..]
+        .iter()
+        .position(|l| l.trim_start().starts_with(')'))
+        .ok_or(ScaffoldError::MissingDaysList)?
+        + start;
+
+    let mut entries = lines
+        .drain(start..end)
+        .map(|l| l.trim().trim_end_matches(',').to_owned())
+        .collect::<Vec<_>>();
+    let indent = lines[start - 1].len() - lines[start - 1].trim_start().len() + 4;
+
+    let entry_idx = entries
+        .iter()
+        .position(|e| matches!(parse_day(e), Some(d) if d > day))
+        .unwrap_or(entries.len());
+    entries.insert(entry_idx, entry);
+
+    let last = entries.len() - 1;
+    let entries = entries.into_iter().enumerate().map(|(idx, e)| {
+        let sep = if idx == last { "" } else { "," };
+        format!("{:indent$}{}{}", "", e, sep, indent = indent)
+    });
+    lines.splice(start..start, entries);
+
+    let mut registered = lines.join("\n");
+    registered.push('\n');
+
+    Ok(registered)
+}
+
+fn parse_day(s: &str) -> Option<usize> {
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+
+    s[..digits].parse().ok()
+}
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    DayOutOfRange(usize),
+    AlreadyExists(PathBuf),
+    AlreadyRegistered(usize),
+    MissingModules,
+    MissingDaysList,
+    Io(io::Error),
+}
+
+impl From<io::Error> for ScaffoldError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DayOutOfRange(day) => write!(f, "day {} is not between 1 and 25", day),
+            Self::AlreadyExists(path) => write!(f, "{} already exists", path.display()),
+            Self::AlreadyRegistered(day) => write!(f, "day {} is already registered", day),
+            Self::MissingModules => write!(f, "no day modules found in main.rs"),
+            Self::MissingDaysList => write!(f, "no days! list found in main.rs"),
+            Self::Io(io_err) => write!(f, "{}", io_err),
+        }
+    }
+}
+
+impl error::Error for ScaffoldError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(io_err) => Some(io_err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    const MAIN: &str = "mod day01;
+mod day02;
+
+advent_of_code::days!(
+    01 => (1, 2),
+    02 => (3, 4)
+);
+";
+
+    #[test]
+    fn test() {
+        assert_eq!(
+            super::register(MAIN, 11).unwrap(),
+            "mod day01;
+mod day02;
+mod day11;
+
+advent_of_code::days!(
+    01 => (1, 2),
+    02 => (3, 4),
+    11 => (None, None)
+);
+"
+        );
+    }
+}
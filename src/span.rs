@@ -0,0 +1,152 @@
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub name: &'static str,
+    pub elapsed: Duration,
+    pub calls: u32,
+    pub children: Vec<Span>,
+}
+
+impl Span {
+    pub fn new(name: &'static str, elapsed: Duration, children: Vec<Span>) -> Self {
+        Self {
+            name,
+            elapsed,
+            calls: 1,
+            children,
+        }
+    }
+
+    pub fn lines(&self, depth: usize) -> Vec<String> {
+        let calls = match self.calls {
+            1 => String::new(),
+            calls => format!(" x{}", calls),
+        };
+        let line = format!(
+            "{:indent$}{}({:?}){}",
+            "",
+            self.name,
+            self.elapsed,
+            calls,
+            indent = depth * 4
+        );
+
+        std::iter::once(line)
+            .chain(self.children.iter().flat_map(|c| c.lines(depth + 1)))
+            .collect()
+    }
+}
+
+/// Spans with the same name under the same parent are folded together, so a span entered in a
+/// loop shows up once with its total time and call count.
+fn merge(spans: &mut Vec<Span>, span: Span) {
+    match spans.iter_mut().find(|s| s.name == span.name) {
+        Some(existing) => {
+            existing.elapsed += span.elapsed;
+            existing.calls += span.calls;
+            span.children
+                .into_iter()
+                .for_each(|c| merge(&mut existing.children, c));
+        }
+        None => spans.push(span),
+    }
+}
+
+thread_local! {
+    static FRAMES: RefCell<Vec<Span>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `fun` and returns the spans entered while it ran on this thread.
+pub fn collect<F, R>(fun: F) -> (R, Vec<Span>)
+where
+    F: FnOnce() -> R,
+{
+    FRAMES.with(|frames| {
+        frames
+            .borrow_mut()
+            .push(Span::new("", Duration::ZERO, Vec::new()))
+    });
+    let res = fun();
+    let root = FRAMES.with(|frames| frames.borrow_mut().pop().unwrap());
+
+    (res, root.children)
+}
+
+#[derive(Debug)]
+pub struct Guard {
+    start: Instant,
+}
+
+/// Opens a span that closes when the returned guard is dropped. Outside of `collect` this does
+/// nothing, so solutions can be instrumented without affecting callers that don't care.
+pub fn enter(name: &'static str) -> Option<Guard> {
+    FRAMES.with(|frames| {
+        let mut frames = frames.borrow_mut();
+        if frames.is_empty() {
+            None
+        } else {
+            frames.push(Span::new(name, Duration::ZERO, Vec::new()));
+            Some(Guard {
+                start: Instant::now(),
+            })
+        }
+    })
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+
+        FRAMES.with(|frames| {
+            let mut frames = frames.borrow_mut();
+            if let Some(mut span) = frames.pop() {
+                span.elapsed = elapsed;
+                if let Some(parent) = frames.last_mut() {
+                    merge(&mut parent.children, span);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(feature = "spans")]
+#[macro_export]
+macro_rules! span {
+    ($name: expr) => {
+        let _span = advent_of_code::span::enter($name);
+    };
+}
+
+#[cfg(not(feature = "spans"))]
+#[macro_export]
+macro_rules! span {
+    ($name: expr) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect, enter};
+
+    #[test]
+    fn test() {
+        let (res, spans) = collect(|| {
+            let _outer = enter("outer");
+            for _ in 0..3 {
+                let _inner = enter("inner");
+            }
+
+            42
+        });
+
+        assert_eq!(res, 42);
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].name, "outer");
+        assert_eq!(spans[0].children.len(), 1);
+        assert_eq!(spans[0].children[0].calls, 3);
+        assert!(enter("stray").is_none());
+    }
+}
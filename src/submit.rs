@@ -0,0 +1,385 @@
+use std::{
+    env, error, fmt, fs, io,
+    path::{Path, PathBuf},
+    str,
+    time::Duration,
+};
+
+use crate::{
+    http::{Client, CurlClient, HttpError, Request, Throttled},
+    input::{self, InputError},
+};
+
+pub trait Answer {
+    fn answer(&self) -> Option<String>;
+}
+
+macro_rules! impl_answer {
+    ($($ty: ty),+) => {
+        $(
+            impl Answer for $ty {
+                fn answer(&self) -> Option<String> {
+                    Some(self.to_string())
+                }
+            }
+        )+
+    };
+}
+
+impl_answer!(i32, i64, u32, u64, usize);
+
+impl<T: Answer> Answer for Option<T> {
+    fn answer(&self) -> Option<String> {
+        self.as_ref().and_then(Answer::answer)
+    }
+}
+
+impl<T: Answer, E> Answer for Result<T, E> {
+    fn answer(&self) -> Option<String> {
+        self.as_ref().ok().and_then(Answer::answer)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    Wait(Option<Duration>),
+    WrongLevel,
+}
+
+impl Verdict {
+    fn parse(page: &str) -> Option<Self> {
+        if page.contains("That's the right answer") {
+            Some(Self::Correct)
+        } else if page.contains("your answer is too high") {
+            Some(Self::TooHigh)
+        } else if page.contains("your answer is too low") {
+            Some(Self::TooLow)
+        } else if page.contains("That's not the right answer") {
+            Some(Self::Incorrect)
+        } else if page.contains("You gave an answer too recently") {
+            Some(Self::Wait(parse_wait(page)))
+        } else if page.contains("You don't seem to be solving the right level") {
+            Some(Self::WrongLevel)
+        } else {
+            None
+        }
+    }
+
+    fn is_final(&self) -> bool {
+        matches!(
+            self,
+            Self::Correct | Self::TooHigh | Self::TooLow | Self::Incorrect
+        )
+    }
+}
+
+fn parse_wait(page: &str) -> Option<Duration> {
+    let (_, rest) = page.split_once("You have ")?;
+    let (left, _) = rest.split_once(" left to wait")?;
+
+    left.split_ascii_whitespace()
+        .try_fold(0, |secs, unit| {
+            match unit.split_at(unit.len().checked_sub(1)?) {
+                (n, "m") => Some(secs + n.parse::<u64>().ok()? * 60),
+                (n, "s") => Some(secs + n.parse::<u64>().ok()?),
+                _ => None,
+            }
+        })
+        .map(Duration::from_secs)
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::TooHigh => write!(f, "too-high"),
+            Self::TooLow => write!(f, "too-low"),
+            Self::Incorrect => write!(f, "incorrect"),
+            Self::Wait(Some(wait)) => write!(f, "wait {:?}", wait),
+            Self::Wait(None) => write!(f, "wait"),
+            Self::WrongLevel => write!(f, "wrong-level"),
+        }
+    }
+}
+
+impl str::FromStr for Verdict {
+    type Err = SubmitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(Self::Correct),
+            "too-high" => Ok(Self::TooHigh),
+            "too-low" => Ok(Self::TooLow),
+            "incorrect" => Ok(Self::Incorrect),
+            _ => Err(SubmitError::CorruptVerdicts(String::from(s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+    AlreadyCorrect(String),
+    KnownWrong(Verdict),
+    NotBelow(i128),
+    NotAbove(i128),
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyCorrect(answer) => write!(f, "already solved with {}", answer),
+            Self::KnownWrong(verdict) => write!(f, "already submitted as {}", verdict),
+            Self::NotBelow(high) => write!(f, "{} was already too high", high),
+            Self::NotAbove(low) => write!(f, "{} was already too low", low),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Submitter<C> {
+    pub base_url: String,
+    pub session_file: PathBuf,
+    pub verdict_dir: PathBuf,
+    client: C,
+}
+
+impl<C: Client> Submitter<C> {
+    pub fn new(base_url: String, session_file: PathBuf, verdict_dir: PathBuf, client: C) -> Self {
+        Self {
+            base_url,
+            session_file,
+            verdict_dir,
+            client,
+        }
+    }
+
+    fn verdicts_path(&self, day: usize, part: u8) -> PathBuf {
+        self.verdict_dir.join(format!("{:02}-{}.txt", day, part))
+    }
+
+    pub fn verdicts(&self, day: usize, part: u8) -> Result<Vec<(String, Verdict)>, SubmitError> {
+        let log = match fs::read_to_string(self.verdicts_path(day, part)) {
+            Ok(log) => log,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        log.lines()
+            .map(|l| {
+                let (answer, verdict) = l
+                    .split_once('\t')
+                    .ok_or_else(|| SubmitError::CorruptVerdicts(String::from(l)))?;
+
+                Ok((String::from(answer), verdict.parse()?))
+            })
+            .collect()
+    }
+
+    pub fn check(&self, day: usize, part: u8, answer: &str) -> Result<(), SubmitError> {
+        let verdicts = self.verdicts(day, part)?;
+        let value = answer.parse::<i128>().ok();
+
+        let rejection = verdicts.iter().find_map(|(prev, verdict)| {
+            let prev_value = prev.parse::<i128>().ok();
+
+            match verdict {
+                Verdict::Correct => Some(Rejection::AlreadyCorrect(prev.clone())),
+                _ if prev == answer => Some(Rejection::KnownWrong(*verdict)),
+                Verdict::TooHigh => match (value, prev_value) {
+                    (Some(value), Some(high)) if value >= high => Some(Rejection::NotBelow(high)),
+                    _ => None,
+                },
+                Verdict::TooLow => match (value, prev_value) {
+                    (Some(value), Some(low)) if value <= low => Some(Rejection::NotAbove(low)),
+                    _ => None,
+                },
+                _ => None,
+            }
+        });
+
+        match rejection {
+            Some(rejection) => Err(SubmitError::Rejected(rejection)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn submit(&self, day: usize, part: u8, answer: &str) -> Result<Verdict, SubmitError> {
+        self.check(day, part, answer)?;
+
+        let session = input::session(&self.session_file)?;
+        let url = format!("{}/day/{}/answer", self.base_url.trim_end_matches('/'), day);
+        let body = format!("level={}&answer={}", part, answer);
+
+        let res = self
+            .client
+            .send(&Request::post(url, body).header("Cookie", &format!("session={}", session)))?;
+        if res.status != 200 {
+            return Err(SubmitError::Status(res.status));
+        }
+
+        let verdict = Verdict::parse(&res.body).ok_or(SubmitError::UnknownVerdict)?;
+        if verdict.is_final() {
+            let path = self.verdicts_path(day, part);
+            let mut log = match fs::read_to_string(&path) {
+                Ok(log) => log,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+                Err(err) => return Err(err.into()),
+            };
+            log.push_str(&format!("{}\t{}\n", answer, verdict));
+
+            fs::create_dir_all(&self.verdict_dir)?;
+            fs::write(path, log)?;
+        }
+
+        Ok(verdict)
+    }
+}
+
+impl Submitter<Throttled<CurlClient>> {
+    /// Shares `AOC_BASE_URL` and `AOC_SESSION_FILE` with the input provider and keeps
+    /// verdicts in `verdicts/`, overridable through `AOC_VERDICT_DIR`.
+    pub fn from_env() -> Self {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+        Self::new(
+            input::base_url(),
+            input::session_file(),
+            env::var_os("AOC_VERDICT_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| root.join("verdicts")),
+            Throttled::new(CurlClient, input::REQUEST_INTERVAL),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Rejected(Rejection),
+    Status(u16),
+    UnknownVerdict,
+    CorruptVerdicts(String),
+    Input(InputError),
+    Http(HttpError),
+    Io(io::Error),
+}
+
+impl From<InputError> for SubmitError {
+    fn from(err: InputError) -> Self {
+        Self::Input(err)
+    }
+}
+
+impl From<HttpError> for SubmitError {
+    fn from(err: HttpError) -> Self {
+        Self::Http(err)
+    }
+}
+
+impl From<io::Error> for SubmitError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rejected(rejection) => write!(f, "not submitted, {}", rejection),
+            Self::Status(status) => write!(f, "submitting failed with {}", status),
+            Self::UnknownVerdict => write!(f, "could not find a verdict in the response"),
+            Self::CorruptVerdicts(line) => write!(f, "corrupt verdict entry \"{}\"", line),
+            Self::Input(input_err) => write!(f, "{}", input_err),
+            Self::Http(http_err) => write!(f, "{}", http_err),
+            Self::Io(io_err) => write!(f, "{}", io_err),
+        }
+    }
+}
+
+impl error::Error for SubmitError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Input(input_err) => Some(input_err),
+            Self::Http(http_err) => Some(http_err),
+            Self::Io(io_err) => Some(io_err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use super::{Rejection, SubmitError, Submitter, Verdict};
+    use crate::http::{stub, TcpClient, Throttled};
+
+    #[test]
+    fn test() {
+        let (url, requests) = stub::serve(|_, body| {
+            let page = match body {
+                "level=1&answer=100" => "That's not the right answer; your answer is too high.",
+                "level=1&answer=20" => "That's not the right answer; your answer is too low.",
+                "level=1&answer=30" => {
+                    "You gave an answer too recently. You have 1m 5s left to wait."
+                }
+                "level=1&answer=42" => "That's the right answer!",
+                _ => "",
+            };
+
+            (
+                200,
+                format!("<main><article><p>{}</p></article></main>", page),
+            )
+        });
+
+        let dir = env::temp_dir().join(format!("aoc-submit-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(".session"), "c0ffee").unwrap();
+
+        let submitter = Submitter::new(
+            format!("{}/2021", url),
+            dir.join(".session"),
+            dir.join("verdicts"),
+            Throttled::new(TcpClient, Duration::ZERO),
+        );
+
+        assert_eq!(submitter.submit(1, 1, "100").unwrap(), Verdict::TooHigh);
+        assert_eq!(submitter.submit(1, 1, "20").unwrap(), Verdict::TooLow);
+        assert_eq!(
+            submitter.submit(1, 1, "30").unwrap(),
+            Verdict::Wait(Some(Duration::from_secs(65)))
+        );
+        assert!(matches!(
+            submitter.submit(1, 1, "100"),
+            Err(SubmitError::Rejected(Rejection::KnownWrong(
+                Verdict::TooHigh
+            )))
+        ));
+        assert!(matches!(
+            submitter.submit(1, 1, "150"),
+            Err(SubmitError::Rejected(Rejection::NotBelow(100)))
+        ));
+        assert!(matches!(
+            submitter.submit(1, 1, "15"),
+            Err(SubmitError::Rejected(Rejection::NotAbove(20)))
+        ));
+        assert_eq!(submitter.submit(1, 1, "42").unwrap(), Verdict::Correct);
+        assert!(matches!(
+            submitter.submit(1, 1, "43"),
+            Err(SubmitError::Rejected(Rejection::AlreadyCorrect(_)))
+        ));
+
+        assert_eq!(requests.lock().unwrap().len(), 4);
+        assert_eq!(
+            fs::read_to_string(dir.join("verdicts/01-1.txt")).unwrap(),
+            "100\ttoo-high\n20\ttoo-low\n42\tcorrect\n"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}